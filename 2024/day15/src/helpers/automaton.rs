@@ -0,0 +1,330 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+
+use super::bitgrid::BitGrid;
+use super::coordinate::Coordinate;
+use super::{Grid, Neighborhood, Point};
+
+impl<T> Grid<T> {
+    /// Writes the next generation into `next`, reusing its allocation. The
+    /// rule gets the cell and its neighbors inside the grid.
+    pub fn step_into(
+        &self,
+        next: &mut Self,
+        neighborhood: Neighborhood,
+        rule: impl Fn(&T, &[&T]) -> T,
+    ) {
+        next.inner.clear();
+        next.width = self.width;
+        let mut neighbors = Vec::with_capacity(8);
+        for (point, cell) in self {
+            neighbors.clear();
+            neighbors.extend(
+                point
+                    .neighbors_in(neighborhood)
+                    .filter_map(|p| self.get(&p)),
+            );
+            next.inner.push(rule(cell, &neighbors));
+        }
+    }
+
+    pub fn step(&self, neighborhood: Neighborhood, rule: impl Fn(&T, &[&T]) -> T) -> Self {
+        let mut next = Self {
+            inner: Vec::with_capacity(self.inner.len()),
+            width: self.width,
+        };
+        self.step_into(&mut next, neighborhood, rule);
+        next
+    }
+
+    /// Runs `generations` steps, swapping between two buffers.
+    pub fn run(
+        &self,
+        generations: usize,
+        neighborhood: Neighborhood,
+        rule: impl Fn(&T, &[&T]) -> T,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let mut current = self.clone();
+        let mut next = self.clone();
+        for _ in 0..generations {
+            current.step_into(&mut next, neighborhood, &rule);
+            std::mem::swap(&mut current, &mut next);
+        }
+        current
+    }
+}
+
+impl BitGrid {
+    /// One generation of a rule that only depends on whether a cell is set
+    /// and how many of its neighbors are. Works on whole words at a time.
+    pub fn step(&self, neighborhood: Neighborhood, rule: impl Fn(bool, usize) -> bool) -> Self {
+        let counts = self.neighbor_counts(neighborhood);
+        let dead = !self;
+        let mut next = Self::new(self.width(), self.height());
+        for n in 0..=neighborhood.offsets().len() {
+            let (survive, born) = (rule(true, n), rule(false, n));
+            if !survive && !born {
+                continue;
+            }
+            let with_n = counts.equal_to(u8::try_from(n).unwrap());
+            match (survive, born) {
+                (true, true) => next |= &with_n,
+                (true, false) => next |= &(&with_n & self),
+                _ => next |= &(&with_n & &dead),
+            }
+        }
+        next
+    }
+
+    /// Runs `generations` steps without leaving the bitset.
+    pub fn run(
+        &self,
+        generations: usize,
+        neighborhood: Neighborhood,
+        rule: impl Fn(bool, usize) -> bool,
+    ) -> Self {
+        let mut current = self.clone();
+        for _ in 0..generations {
+            current = current.step(neighborhood, &rule);
+        }
+        current
+    }
+}
+
+impl Grid<bool> {
+    /// Like [`Grid::step`] for rules that only count set neighbors, but
+    /// computed on a [`BitGrid`]. Converting costs as much as a plain step,
+    /// so for more generations use [`Grid::run_counts`] or keep the
+    /// [`BitGrid`] around.
+    pub fn step_counts(
+        &self,
+        neighborhood: Neighborhood,
+        rule: impl Fn(bool, usize) -> bool,
+    ) -> Self {
+        self.run_counts(1, neighborhood, rule)
+    }
+
+    /// Like [`Grid::run`] for rules that only count set neighbors. Converts
+    /// to a [`BitGrid`] once and runs all generations on it.
+    pub fn run_counts(
+        &self,
+        generations: usize,
+        neighborhood: Neighborhood,
+        rule: impl Fn(bool, usize) -> bool,
+    ) -> Self {
+        BitGrid::from(self)
+            .run(generations, neighborhood, rule)
+            .to_grid()
+    }
+}
+
+/// One generation on an unbounded plane, given the set cells. The set is a
+/// `BTreeSet` so it can be hashed for cycle detection.
+pub fn step_sparse<T>(
+    cells: &BTreeSet<Point<T>>,
+    neighborhood: Neighborhood,
+    rule: impl Fn(bool, usize) -> bool,
+) -> BTreeSet<Point<T>>
+where
+    T: Coordinate,
+{
+    let mut counts: HashMap<Point<T>, usize> = HashMap::new();
+    for cell in cells {
+        for neighbor in cell.neighbors_in(neighborhood) {
+            *counts.entry(neighbor).or_default() += 1;
+        }
+    }
+    let mut next: BTreeSet<Point<T>> = cells
+        .iter()
+        .filter(|cell| rule(true, counts.get(cell).copied().unwrap_or(0)))
+        .copied()
+        .collect();
+    next.extend(
+        counts
+            .into_iter()
+            .filter(|(point, count)| !cells.contains(point) && rule(false, *count))
+            .map(|(point, _)| point),
+    );
+    next
+}
+
+/// Generation `start` is the first one that repeats, every `period`
+/// generations. A period of 1 is a steady state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest generation with the same state as generation `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Steps any state with a closure and remembers every generation, so cycles
+/// are detected and far generations can be looked up through them.
+pub struct Automaton<S, F> {
+    history: Vec<S>,
+    seen: HashMap<u64, Vec<usize>>,
+    step: F,
+    cycle: Option<Cycle>,
+}
+
+fn fingerprint<S: Hash>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<S, F> Automaton<S, F>
+where
+    S: Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    pub fn new(initial: S, step: F) -> Self {
+        let mut seen = HashMap::new();
+        seen.insert(fingerprint(&initial), vec![0]);
+        Self {
+            history: vec![initial],
+            seen,
+            step,
+            cycle: None,
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        self.history.len() - 1
+    }
+
+    pub fn current(&self) -> &S {
+        &self.history[self.generation()]
+    }
+
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Computes the next generation, unless a cycle was already found.
+    pub fn step(&mut self) -> Option<Cycle> {
+        if self.cycle.is_some() {
+            return self.cycle;
+        }
+        let next = (self.step)(&self.history[self.history.len() - 1]);
+        let generation = self.history.len();
+        let same = self.seen.entry(fingerprint(&next)).or_default();
+        if let Some(start) = same.iter().find(|i| self.history[**i] == next) {
+            self.cycle = Some(Cycle {
+                start: *start,
+                period: generation - start,
+            });
+        } else {
+            same.push(generation);
+            self.history.push(next);
+        }
+        self.cycle
+    }
+
+    /// Only returns once the states repeat.
+    pub fn find_cycle(&mut self) -> Cycle {
+        loop {
+            if let Some(cycle) = self.step() {
+                return cycle;
+            }
+        }
+    }
+
+    /// The state after `n` generations, skipping ahead through the cycle
+    /// once it is found.
+    pub fn nth(&mut self, n: usize) -> &S {
+        while self.generation() < n {
+            if self.step().is_some() {
+                break;
+            }
+        }
+        match self.cycle {
+            Some(cycle) => &self.history[cycle.equivalent(n)],
+            None => &self.history[n],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn life(alive: bool, neighbors: usize) -> bool {
+        neighbors == 3 || (alive && neighbors == 2)
+    }
+
+    #[test]
+    fn blinker() {
+        let grid: Grid<char> = Grid::from_str(".....\n..#..\n..#..\n..#..\n.....\n");
+        let rule = |cell: &char, neighbors: &[&char]| {
+            let count = neighbors.iter().filter(|n| ***n == '#').count();
+            if life(*cell == '#', count) {
+                '#'
+            } else {
+                '.'
+            }
+        };
+        let horizontal = grid.step(Neighborhood::All, rule);
+        assert_eq!(
+            horizontal.to_string(),
+            ".....\n.....\n.###.\n.....\n.....\n"
+        );
+        assert_eq!(grid.run(10, Neighborhood::All, rule), grid);
+
+        let bits = grid.transform(|c| c == '#');
+        assert_eq!(
+            bits.step_counts(Neighborhood::All, life),
+            horizontal.clone().transform(|c| c == '#')
+        );
+        assert_eq!(bits.run_counts(10, Neighborhood::All, life), bits);
+
+        // stays a bitset from generation to generation
+        let mut automaton = Automaton::new(BitGrid::from(&bits), |g: &BitGrid| {
+            g.step(Neighborhood::All, life)
+        });
+        assert_eq!(
+            automaton.find_cycle(),
+            Cycle {
+                start: 0,
+                period: 2
+            }
+        );
+        assert_eq!(
+            automaton.nth(1_000_001).to_grid(),
+            horizontal.transform(|c| c == '#')
+        );
+    }
+
+    #[test]
+    fn sparse_steady_state() {
+        // becomes a block after one generation
+        let cells: BTreeSet<Point<i64>> = [(0, 0), (1, 0), (0, 1)]
+            .into_iter()
+            .map(|(x, y)| Point { x, y })
+            .collect();
+        let mut automaton =
+            Automaton::new(cells, |cells| step_sparse(cells, Neighborhood::All, life));
+        assert_eq!(
+            automaton.find_cycle(),
+            Cycle {
+                start: 1,
+                period: 1
+            }
+        );
+        assert_eq!(automaton.nth(50).len(), 4);
+        assert_eq!(automaton.nth(0).len(), 3);
+    }
+}
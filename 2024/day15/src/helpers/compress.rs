@@ -0,0 +1,246 @@
+use super::polygon::{Location, Polygon};
+use super::regions::Labeling;
+use super::{Grid, Point, Vector};
+
+/// Sorted breakpoints along one axis. Cell `i` covers the half-open range
+/// `breaks[i]..breaks[i + 1]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Axis {
+    breaks: Vec<i64>,
+}
+
+impl Axis {
+    fn new(breaks: impl IntoIterator<Item = i64>) -> Self {
+        let mut breaks: Vec<i64> = breaks.into_iter().collect();
+        breaks.sort_unstable();
+        breaks.dedup();
+        assert!(breaks.len() >= 2, "need at least two breakpoints per axis");
+        Self { breaks }
+    }
+
+    fn len(&self) -> usize {
+        self.breaks.len() - 1
+    }
+
+    fn index_of(&self, v: i64) -> Option<usize> {
+        if v < self.breaks[0] || v >= self.breaks[self.len()] {
+            return None;
+        }
+        Some(self.breaks.partition_point(|b| *b <= v) - 1)
+    }
+
+    fn size(&self, i: usize) -> u64 {
+        self.breaks[i].abs_diff(self.breaks[i + 1])
+    }
+}
+
+/// Maps huge, sparse coordinates onto a small [`Grid`] whose cells stand for
+/// rectangles of different sizes. Everything that only changes at the
+/// breakpoints (rectangle edges, polygon corners) is the same for all real
+/// points inside one cell, so results on the small grid can be weighted back
+/// to real totals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression {
+    xs: Axis,
+    ys: Axis,
+}
+
+impl Compression {
+    pub fn new(xs: impl IntoIterator<Item = i64>, ys: impl IntoIterator<Item = i64>) -> Self {
+        Self {
+            xs: Axis::new(xs),
+            ys: Axis::new(ys),
+        }
+    }
+
+    /// Every point gets a cell of its own, one unit wide and high.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point<i64>>) -> Self {
+        let (xs, ys): (Vec<_>, Vec<_>) = points
+            .into_iter()
+            .map(|p| ([p.x, p.x + 1], [p.y, p.y + 1]))
+            .unzip();
+        Self::new(xs.into_iter().flatten(), ys.into_iter().flatten())
+    }
+
+    /// Breakpoints at every vertex, see [`Compression::polygon_mask`].
+    pub fn from_polygon(polygon: &Polygon) -> Self {
+        Self::from_points(polygon.vertices())
+    }
+
+    pub fn width(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.ys.len()
+    }
+
+    /// A compressed grid with every cell set to `value`.
+    pub fn grid<T: Clone>(&self, value: T) -> Grid<T> {
+        Grid::new(self.width(), self.height(), value)
+    }
+
+    /// The compressed cell containing the real point.
+    pub fn cell_of(&self, point: &Point<i64>) -> Option<Point> {
+        Some(Point {
+            x: self.xs.index_of(point.x)?,
+            y: self.ys.index_of(point.y)?,
+        })
+    }
+
+    /// The real point at the top left of the cell.
+    pub fn origin(&self, cell: &Point) -> Point<i64> {
+        Point {
+            x: self.xs.breaks[cell.x],
+            y: self.ys.breaks[cell.y],
+        }
+    }
+
+    pub fn cell_width(&self, cell: &Point) -> u64 {
+        self.xs.size(cell.x)
+    }
+
+    pub fn cell_height(&self, cell: &Point) -> u64 {
+        self.ys.size(cell.y)
+    }
+
+    pub fn cell_area(&self, cell: &Point) -> u128 {
+        u128::from(self.cell_width(cell)) * u128::from(self.cell_height(cell))
+    }
+
+    /// The cells covering the real rectangle from `min` to `max`, inclusive.
+    /// Its edges should line up with the breakpoints.
+    pub fn cells_in(&self, min: &Point<i64>, max: &Point<i64>) -> impl Iterator<Item = Point> {
+        let first = self.cell_of(min).expect("rectangle outside of compression");
+        let last = self.cell_of(max).expect("rectangle outside of compression");
+        (first.y..=last.y).flat_map(move |y| (first.x..=last.x).map(move |x| Point { x, y }))
+    }
+
+    /// The real area covered by the cells.
+    pub fn area<'a>(&self, cells: impl IntoIterator<Item = &'a Point>) -> u128 {
+        cells.into_iter().map(|cell| self.cell_area(cell)).sum()
+    }
+
+    /// The real area of the set cells.
+    pub fn mask_area(&self, mask: &Grid<bool>) -> u128 {
+        self.area(mask.positions_of(&true).collect::<Vec<_>>().iter())
+    }
+
+    pub fn region_area(&self, labeling: &Labeling, label: usize) -> u128 {
+        self.area(labeling.points(label))
+    }
+
+    /// The real length of the region's outline.
+    pub fn region_perimeter(&self, labeling: &Labeling, label: usize) -> u128 {
+        let mut perimeter = 0;
+        for cell in labeling.points(label) {
+            for direction in Vector::ORTHOGONAL {
+                let outside =
+                    cell.checked_add(direction).and_then(|n| labeling.label(&n)) != Some(label);
+                if outside {
+                    perimeter += u128::from(if direction.x == 0 {
+                        self.cell_width(cell)
+                    } else {
+                        self.cell_height(cell)
+                    });
+                }
+            }
+        }
+        perimeter
+    }
+
+    /// Marks the cells inside the polygon, including its boundary, treating
+    /// every real point as a unit square. Exact for polygons with axis
+    /// parallel edges whose vertices are all breakpoints, e.g. after
+    /// [`Compression::from_polygon`]. Every cell stands for its top left
+    /// point, and each row of them is located in one sweep.
+    pub fn polygon_mask(&self, polygon: &Polygon) -> Grid<bool> {
+        let mut mask = self.grid(false);
+        let columns = &self.xs.breaks[..self.width()];
+        for y in 0..self.height() {
+            let row = polygon.locate_row(self.ys.breaks[y], columns);
+            for (x, location) in row.into_iter().enumerate() {
+                mask[&Point { x, y }] = location != Location::Outside;
+            }
+        }
+        mask
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dig(plan: &[(char, i64)]) -> Polygon {
+        let steps = plan.iter().map(|(direction, length)| {
+            let direction = match direction {
+                'R' => Vector { x: 1, y: 0 },
+                'D' => Vector { x: 0, y: 1 },
+                'L' => Vector { x: -1, y: 0 },
+                _ => Vector { x: 0, y: -1 },
+            };
+            (direction, *length)
+        });
+        Polygon::from_steps(Point { x: 0, y: 0 }, steps)
+    }
+
+    #[test]
+    fn lagoon() {
+        let polygon = dig(&[
+            ('R', 6),
+            ('D', 5),
+            ('L', 2),
+            ('D', 2),
+            ('R', 2),
+            ('D', 2),
+            ('L', 5),
+            ('U', 2),
+            ('L', 1),
+            ('U', 2),
+            ('R', 2),
+            ('U', 3),
+            ('L', 2),
+            ('U', 2),
+        ]);
+        let compression = Compression::from_polygon(&polygon);
+        let mask = compression.polygon_mask(&polygon);
+        assert_eq!(compression.mask_area(&mask), 62);
+
+        let labeling = mask.regions();
+        let lagoon = labeling.label(&Point { x: 0, y: 0 }).unwrap();
+        assert_eq!(compression.region_area(&labeling, lagoon), 62);
+        assert_eq!(compression.region_perimeter(&labeling, lagoon), 42);
+        assert_eq!(
+            compression.cell_of(&Point { x: 3, y: 4 }),
+            Some(Point { x: 3, y: 3 })
+        );
+        assert_eq!(compression.cell_height(&Point { x: 3, y: 3 }), 2);
+        let top: Vec<Point> = compression
+            .cells_in(&Point { x: 0, y: 0 }, &Point { x: 6, y: 0 })
+            .collect();
+        assert_eq!(compression.area(&top), 7);
+    }
+
+    #[test]
+    fn huge_lagoon() {
+        let polygon = dig(&[
+            ('R', 461_937),
+            ('D', 56_407),
+            ('R', 356_671),
+            ('D', 863_240),
+            ('R', 367_720),
+            ('D', 266_681),
+            ('L', 577_262),
+            ('U', 829_975),
+            ('L', 112_010),
+            ('D', 829_975),
+            ('L', 491_645),
+            ('U', 686_074),
+            ('L', 5_411),
+            ('U', 500_254),
+        ]);
+        let compression = Compression::from_polygon(&polygon);
+        let mask = compression.polygon_mask(&polygon);
+        assert_eq!(compression.mask_area(&mask), 952_408_144_115);
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// The integer types points and vectors can be made of. Every type has a
+/// signed `Delta` type for the difference between two values and an unsigned
+/// `Distance` type for absolute differences, e.g. `isize` and `usize` for
+/// `usize`.
+pub trait Coordinate:
+    Copy
+    + Debug
+    + Default
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + TryFrom<usize>
+    + TryInto<usize>
+{
+    type Delta: Signed;
+    type Distance: Coordinate;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+    fn wrapping_neg(self) -> Self;
+
+    /// `None` if the result does not fit in `Self`.
+    fn checked_add_delta(self, delta: Self::Delta) -> Option<Self>;
+    /// The difference `to - self`, or `None` if it does not fit in `Delta`.
+    fn checked_delta_to(self, to: Self) -> Option<Self::Delta>;
+
+    fn abs_diff(self, other: Self) -> Self::Distance;
+    fn unsigned_abs(self) -> Self::Distance;
+    fn signum(self) -> Self;
+}
+
+pub trait Signed: Coordinate<Delta = Self> + Neg<Output = Self> {
+    const NEG_ONE: Self;
+}
+
+macro_rules! coordinate {
+    ($unsigned:ty, $signed:ty) => {
+        impl Coordinate for $unsigned {
+            type Delta = $signed;
+            type Distance = $unsigned;
+
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                self.checked_sub(other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                self.checked_mul(other)
+            }
+
+            fn checked_neg(self) -> Option<Self> {
+                self.checked_neg()
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                self.wrapping_sub(other)
+            }
+
+            fn wrapping_mul(self, other: Self) -> Self {
+                self.wrapping_mul(other)
+            }
+
+            fn wrapping_neg(self) -> Self {
+                self.wrapping_neg()
+            }
+
+            fn checked_add_delta(self, delta: $signed) -> Option<Self> {
+                self.checked_add_signed(delta)
+            }
+
+            fn checked_delta_to(self, to: Self) -> Option<$signed> {
+                if to >= self {
+                    <$signed>::checked_add_unsigned(0, to - self)
+                } else {
+                    <$signed>::checked_sub_unsigned(0, self - to)
+                }
+            }
+
+            fn abs_diff(self, other: Self) -> Self {
+                self.abs_diff(other)
+            }
+
+            fn unsigned_abs(self) -> Self {
+                self
+            }
+
+            fn signum(self) -> Self {
+                Self::from(self != 0)
+            }
+        }
+
+        impl Coordinate for $signed {
+            type Delta = $signed;
+            type Distance = $unsigned;
+
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                self.checked_sub(other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                self.checked_mul(other)
+            }
+
+            fn checked_neg(self) -> Option<Self> {
+                self.checked_neg()
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                self.wrapping_sub(other)
+            }
+
+            fn wrapping_mul(self, other: Self) -> Self {
+                self.wrapping_mul(other)
+            }
+
+            fn wrapping_neg(self) -> Self {
+                self.wrapping_neg()
+            }
+
+            fn checked_add_delta(self, delta: Self) -> Option<Self> {
+                self.checked_add(delta)
+            }
+
+            fn checked_delta_to(self, to: Self) -> Option<Self> {
+                to.checked_sub(self)
+            }
+
+            fn abs_diff(self, other: Self) -> $unsigned {
+                self.abs_diff(other)
+            }
+
+            fn unsigned_abs(self) -> $unsigned {
+                self.unsigned_abs()
+            }
+
+            fn signum(self) -> Self {
+                self.signum()
+            }
+        }
+
+        impl Signed for $signed {
+            const NEG_ONE: Self = -1;
+        }
+    };
+}
+
+coordinate!(u8, i8);
+coordinate!(u16, i16);
+coordinate!(u32, i32);
+coordinate!(u64, i64);
+coordinate!(u128, i128);
+coordinate!(usize, isize);
+
+/// Euclid's algorithm on signed values, so the result may be negative. See
+/// [`super::number::gcd`] for plain unsigned numbers.
+pub(super) fn gcd<T: Coordinate>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        // ±1 divides everything, and T::MIN % -1 would overflow
+        if b == T::ONE || b.checked_neg() == Some(T::ONE) {
+            return b;
+        }
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deltas() {
+        assert_eq!(3_usize.checked_delta_to(1), Some(-2_isize));
+        assert_eq!(0_u8.checked_delta_to(200), None);
+        assert_eq!(200_u8.checked_delta_to(72), Some(i8::MIN));
+        assert_eq!(5_u32.checked_add_delta(-6), None);
+        assert_eq!((-5_i64).abs_diff(5), 10_u64);
+        assert_eq!(Coordinate::signum(7_u16), 1);
+    }
+}
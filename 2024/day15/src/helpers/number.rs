@@ -0,0 +1,456 @@
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+/// Greatest common divisor, with `gcd(0, 0) == 0`.
+pub const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it does not fit.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+pub fn gcd_all(numbers: impl IntoIterator<Item = u64>) -> u64 {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// The first time all cycles line up again. `Some(1)` for no numbers.
+pub fn lcm_all(numbers: impl IntoIterator<Item = u64>) -> Option<u64> {
+    numbers.into_iter().try_fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g`, where `g` is the
+/// non-negative gcd. The coefficients stay below `a` and `b` in size, so
+/// anything up to 64 bit is safe.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut previous, mut remainder) = (a, b);
+    let (mut previous_a, mut coefficient_a) = (1, 0);
+    let (mut previous_b, mut coefficient_b) = (0, 1);
+    while remainder != 0 {
+        let quotient = previous / remainder;
+        (previous, remainder) = (remainder, previous - quotient * remainder);
+        (previous_a, coefficient_a) = (coefficient_a, previous_a - quotient * coefficient_a);
+        (previous_b, coefficient_b) = (coefficient_b, previous_b - quotient * coefficient_b);
+    }
+    if previous < 0 {
+        (-previous, -previous_a, -previous_b)
+    } else {
+        (previous, previous_a, previous_b)
+    }
+}
+
+/// The `x` with `a * x ≡ 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    let (g, x, _) = extended_gcd(i128::from(a), i128::from(modulus));
+    (g == 1).then(|| u64::try_from(x.rem_euclid(i128::from(modulus))).unwrap())
+}
+
+pub fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    u64::try_from(u128::from(a) * u128::from(b) % u128::from(modulus)).unwrap()
+}
+
+/// `base` to the power of `exp`, by squaring.
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+/// All `x` with `x ≡ residue (mod modulus)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    pub residue: u64,
+    pub modulus: u64,
+}
+
+impl Congruence {
+    /// Negative residues are fine, e.g. an offset before the cycle starts.
+    pub fn new(residue: i64, modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        Self {
+            residue: u64::try_from(i128::from(residue).rem_euclid(i128::from(modulus))).unwrap(),
+            modulus,
+        }
+    }
+
+    pub const fn contains(&self, x: u64) -> bool {
+        x % self.modulus == self.residue
+    }
+
+    /// The `x` satisfying both congruences, modulo the lcm of the moduli. The
+    /// moduli do not have to be coprime. `None` if the congruences contradict
+    /// each other or the lcm does not fit.
+    pub fn merge(self, other: Self) -> Option<Self> {
+        let g = gcd(self.modulus, other.modulus);
+        let difference = i128::from(other.residue) - i128::from(self.residue);
+        if difference % i128::from(g) != 0 {
+            return None;
+        }
+        let modulus = lcm(self.modulus, other.modulus)?;
+        let step = other.modulus / g;
+        // self.residue + k * self.modulus ≡ other.residue (mod other.modulus)
+        let inverse = mod_inverse(self.modulus / g % step, step)?;
+        let k = u64::try_from((difference / i128::from(g)).rem_euclid(i128::from(step))).unwrap();
+        let k = mod_mul(k, inverse, step);
+        Some(Self {
+            residue: self.residue + k * self.modulus,
+            modulus,
+        })
+    }
+
+    /// The smallest solution that is at least `n`.
+    pub fn first_from(&self, n: u64) -> u64 {
+        let behind = n % self.modulus;
+        if behind <= self.residue {
+            n + (self.residue - behind)
+        } else {
+            n + (self.modulus - behind) + self.residue
+        }
+    }
+}
+
+/// Chinese remainder theorem over any number of congruences. `None` if they
+/// have no common solution.
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Option<Congruence> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence::new(0, 1), Congruence::merge)
+}
+
+/// The largest `r` with `r * r <= n`, by Newton's method.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // a power of two at least as large as the root
+    let mut x: u128 = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = x.midpoint(n / x);
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+pub fn isqrt_u64(n: u64) -> u64 {
+    u64::try_from(isqrt(u128::from(n))).unwrap()
+}
+
+/// A set of integers, see [`Quadratic::positive`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegerSet {
+    Empty,
+    All,
+    Between(RangeInclusive<i128>),
+    AtMost(i128),
+    AtLeast(i128),
+    /// Everything up to `below` and everything from `above` on.
+    Outside {
+        below: i128,
+        above: i128,
+    },
+}
+
+impl IntegerSet {
+    pub fn contains(&self, x: i128) -> bool {
+        match self {
+            Self::Empty => false,
+            Self::All => true,
+            Self::Between(range) => range.contains(&x),
+            Self::AtMost(high) => x <= *high,
+            Self::AtLeast(low) => x >= *low,
+            Self::Outside { below, above } => x <= *below || x >= *above,
+        }
+    }
+
+    /// The number of integers in the set, if it is finite.
+    pub fn count(&self) -> Option<u128> {
+        match self {
+            Self::Empty => Some(0),
+            Self::Between(range) => Some(range.end().abs_diff(*range.start()) + 1),
+            _ => None,
+        }
+    }
+
+    fn complement(self) -> Self {
+        match self {
+            Self::Empty => Self::All,
+            Self::Between(range) => Self::Outside {
+                below: range.start() - 1,
+                above: range.end() + 1,
+            },
+            Self::AtMost(high) => Self::AtLeast(high + 1),
+            Self::AtLeast(low) => Self::AtMost(low - 1),
+            Self::Outside { below, above } if below + 1 < above => {
+                Self::Between(below + 1..=above - 1)
+            }
+            Self::All | Self::Outside { .. } => Self::Empty,
+        }
+    }
+}
+
+/// `a * x² + b * x + c` with integer coefficients, evaluated exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quadratic {
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+impl Quadratic {
+    /// `None` on overflow.
+    pub fn checked_at(&self, x: i128) -> Option<i128> {
+        evaluate(
+            i128::from(self.a),
+            i128::from(self.b),
+            i128::from(self.c),
+            x,
+        )
+    }
+
+    pub fn at(&self, x: i128) -> i128 {
+        self.checked_at(x).expect("quadratic overflow")
+    }
+
+    /// The integers where the value is above zero, like the hold times that
+    /// beat the record in 2023 day 6. `None` if the values around the roots
+    /// do not fit in an `i128`.
+    pub fn positive(&self) -> Option<IntegerSet> {
+        self.solve(true)
+    }
+
+    /// Like [`Quadratic::positive`], including the roots.
+    pub fn non_negative(&self) -> Option<IntegerSet> {
+        self.solve(false)
+    }
+
+    fn solve(&self, strict: bool) -> Option<IntegerSet> {
+        let (a, b, c) = (i128::from(self.a), i128::from(self.b), i128::from(self.c));
+        match a.cmp(&0) {
+            Ordering::Less => downwards(-a, b, c, strict),
+            // above zero exactly where the negated parabola is not
+            Ordering::Greater => Some(downwards(a, -b, -c, !strict)?.complement()),
+            Ordering::Equal => Some(linear(b, c, strict)),
+        }
+    }
+}
+
+fn evaluate(a: i128, b: i128, c: i128, x: i128) -> Option<i128> {
+    a.checked_mul(x)?
+        .checked_add(b)?
+        .checked_mul(x)?
+        .checked_add(c)
+}
+
+fn holds(value: i128, strict: bool) -> bool {
+    if strict {
+        value > 0
+    } else {
+        value >= 0
+    }
+}
+
+/// Where `b * x + c` is positive, or non-negative.
+fn linear(b: i128, c: i128, strict: bool) -> IntegerSet {
+    match b.cmp(&0) {
+        Ordering::Equal if holds(c, strict) => IntegerSet::All,
+        Ordering::Equal => IntegerSet::Empty,
+        Ordering::Greater if strict => IntegerSet::AtLeast((-c).div_euclid(b) + 1),
+        Ordering::Greater => IntegerSet::AtLeast(-c.div_euclid(b)),
+        // mirrored, with x = -y
+        Ordering::Less => match linear(-b, c, strict) {
+            IntegerSet::AtLeast(low) => IntegerSet::AtMost(-low),
+            _ => unreachable!(),
+        },
+    }
+}
+
+/// Where `-a * x² + b * x + c` is positive, or non-negative, for `a > 0`.
+fn downwards(a: i128, b: i128, c: i128, strict: bool) -> Option<IntegerSet> {
+    // b² + 4ac, kept unsigned so it only overflows for huge positive values
+    let square = b.unsigned_abs() * b.unsigned_abs();
+    let product = 4_u128
+        .checked_mul(a.unsigned_abs())
+        .and_then(|p| p.checked_mul(c.unsigned_abs()));
+    let discriminant = if c >= 0 {
+        square.checked_add(product?)?
+    } else {
+        match product {
+            Some(product) if product <= square => square - product,
+            // never reaches zero
+            _ => return Some(IntegerSet::Empty),
+        }
+    };
+    let root = i128::try_from(isqrt(discriminant)).unwrap();
+    // the floored square root puts these within one of the real roots at
+    // (b ± √discriminant) / 2a
+    let mut low = (b - root).div_euclid(2 * a);
+    let mut high = (b + root).div_euclid(2 * a) + 1;
+    let at = |x| Some(holds(evaluate(-a, b, c, x)?, strict));
+    while low <= high && !at(low)? {
+        low += 1;
+    }
+    while high >= low && !at(high)? {
+        high -= 1;
+    }
+    if low > high {
+        return Some(IntegerSet::Empty);
+    }
+    while at(low - 1)? {
+        low -= 1;
+    }
+    while at(high + 1)? {
+        high += 1;
+    }
+    Some(IntegerSet::Between(low..=high))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn divisors() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        // ghosts walking in parallel, like 2023 day 8
+        assert_eq!(
+            lcm_all([12_643, 14_257, 15_871, 18_023, 19_637, 16_409]),
+            Some(11_795_205_644_011)
+        );
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(-4, 6), (2, 1, 1));
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(4, 10), None);
+        assert_eq!(mod_pow(2, 64, u64::MAX), 1);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+    }
+
+    #[test]
+    fn remainders() {
+        // robots line up on both axes, with periods 101 and 103
+        let both = crt([Congruence::new(12, 101), Congruence::new(-3, 103)]).unwrap();
+        assert_eq!(
+            both,
+            Congruence {
+                residue: 5971,
+                modulus: 10_403
+            }
+        );
+        assert!(both.contains(5971 + 10_403 * 5));
+
+        // moduli with a common factor
+        assert_eq!(
+            crt([Congruence::new(2, 6), Congruence::new(8, 10)]),
+            Some(Congruence::new(8, 30))
+        );
+        assert_eq!(crt([Congruence::new(1, 4), Congruence::new(2, 6)]), None);
+        assert_eq!(Congruence::new(8, 30).first_from(9), 38);
+        assert_eq!(Congruence::new(8, 30).first_from(8), 8);
+    }
+
+    #[test]
+    fn square_roots() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(24), 4);
+        assert_eq!(isqrt(25), 5);
+        assert_eq!(isqrt(u128::MAX), u128::from(u64::MAX));
+        assert_eq!(isqrt_u64(u64::MAX), u64::from(u32::MAX));
+        assert_eq!(isqrt_u64((1 << 62) - 1), (1 << 31) - 1);
+    }
+
+    #[test]
+    fn boat_races() {
+        // hold for t of the race time, need t * (time - t) - distance > 0
+        let race = |time: i64, distance: i64| Quadratic {
+            a: -1,
+            b: time,
+            c: -distance,
+        };
+        let ways = |quadratic: Quadratic| quadratic.positive().unwrap().count().unwrap();
+        assert_eq!(
+            ways(race(7, 9)) * ways(race(15, 40)) * ways(race(30, 200)),
+            288
+        );
+        assert_eq!(ways(race(71_530, 940_200)), 71_503);
+        assert_eq!(race(30, 200).positive(), Some(IntegerSet::Between(11..=19)));
+        assert_eq!(
+            race(30, 200).non_negative(),
+            Some(IntegerSet::Between(10..=20))
+        );
+        assert_eq!(race(4, 4).positive(), Some(IntegerSet::Empty));
+        assert_eq!(race(4, 4).non_negative(), Some(IntegerSet::Between(2..=2)));
+        assert_eq!(race(4, 5).non_negative(), Some(IntegerSet::Empty));
+
+        // far beyond what an f64 resolves exactly
+        let record = 2_000_000_000 * 4_000_000_000;
+        assert_eq!(
+            race(6_000_000_000, record).positive(),
+            Some(IntegerSet::Between(2_000_000_001..=3_999_999_999))
+        );
+    }
+
+    #[test]
+    fn any_quadratic() {
+        let huge = Quadratic {
+            a: -(1 << 62),
+            b: i64::MAX,
+            c: i64::MAX,
+        };
+        assert_eq!(huge.positive(), Some(IntegerSet::Between(0..=2)));
+        assert_eq!(
+            Quadratic {
+                a: i64::MIN,
+                b: i64::MAX,
+                c: i64::MAX
+            }
+            .positive(),
+            None
+        );
+
+        // (x - 1)(x - 4), opening upwards
+        let upwards = Quadratic { a: 1, b: -5, c: 4 };
+        assert_eq!(
+            upwards.positive(),
+            Some(IntegerSet::Outside { below: 0, above: 5 })
+        );
+        assert_eq!(
+            upwards.non_negative(),
+            Some(IntegerSet::Outside { below: 1, above: 4 })
+        );
+        let square = Quadratic { a: 1, b: 0, c: 0 };
+        assert_eq!(square.non_negative(), Some(IntegerSet::All));
+        assert_eq!(
+            square.positive(),
+            Some(IntegerSet::Outside {
+                below: -1,
+                above: 1
+            })
+        );
+        assert!(!square.positive().unwrap().contains(0));
+
+        let line = |b, c| Quadratic { a: 0, b, c };
+        assert_eq!(line(2, -3).positive(), Some(IntegerSet::AtLeast(2)));
+        assert_eq!(line(2, -4).positive(), Some(IntegerSet::AtLeast(3)));
+        assert_eq!(line(2, -4).non_negative(), Some(IntegerSet::AtLeast(2)));
+        assert_eq!(line(-2, 3).positive(), Some(IntegerSet::AtMost(1)));
+        assert_eq!(line(0, 0).positive(), Some(IntegerSet::Empty));
+        assert_eq!(line(0, 0).non_negative(), Some(IntegerSet::All));
+    }
+}
@@ -0,0 +1,341 @@
+use std::cmp::Ordering;
+
+use super::coordinate::gcd;
+use super::{Grid, Path, Point, Vector};
+
+/// A closed polygon on the integer lattice. The last vertex connects back to
+/// the first one, so it should not be repeated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point<i64>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point<i64>>) -> Self {
+        Self { vertices }
+    }
+
+    /// Follows `(direction, length)` steps from `start`, like a dig plan. The
+    /// steps have to end where they started.
+    pub fn from_steps(
+        start: Point<i64>,
+        steps: impl IntoIterator<Item = (Vector<i64>, i64)>,
+    ) -> Self {
+        let mut vertices = vec![start];
+        let mut position = start;
+        for (direction, length) in steps {
+            position += direction * length;
+            vertices.push(position);
+        }
+        assert_eq!(vertices.pop(), Some(start), "steps do not form a loop");
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point<i64>] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point<i64>, Point<i64>)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    /// Twice the signed area from the shoelace formula, so it stays an
+    /// integer. Positive when the vertices go clockwise on screen, with y
+    /// pointing down.
+    pub fn double_signed_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| i128::from(a.x) * i128::from(b.y) - i128::from(b.x) * i128::from(a.y))
+            .sum()
+    }
+
+    /// Twice the area, see [`Polygon::double_signed_area`].
+    pub fn double_area(&self) -> u128 {
+        self.double_signed_area().unsigned_abs()
+    }
+
+    /// The number of lattice points on the edges.
+    pub fn boundary_points(&self) -> u128 {
+        self.edges()
+            .map(|(a, b)| {
+                let d = b - a;
+                u128::from(gcd(d.x, d.y).unsigned_abs())
+            })
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside, from Pick's theorem.
+    /// Polygons without area have none.
+    pub fn interior_points(&self) -> u128 {
+        if self.vertices.len() < 3 {
+            return 0;
+        }
+        (self.double_area() + 2)
+            .checked_sub(self.boundary_points())
+            .map_or(0, |twice| twice / 2)
+    }
+
+    pub fn locate(&self, point: &Point<i64>) -> Location {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if on_segment(a, b, *point) {
+                return Location::Boundary;
+            }
+            // half-open, so a vertex on the ray is only counted once
+            if (a.y <= point.y) != (b.y <= point.y) && crossing(a, b, point.y).is_left_of(point.x) {
+                inside = !inside;
+            }
+        }
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    pub fn contains(&self, point: &Point<i64>) -> bool {
+        self.locate(point) != Location::Outside
+    }
+
+    /// Locates the points `(x, y)` for every `x` in `xs`, which has to be
+    /// sorted. Sweeps the row once, instead of going over every edge for
+    /// every point like [`Polygon::locate`].
+    pub fn locate_row(&self, y: i64, xs: &[i64]) -> Vec<Location> {
+        let mut crossings: Vec<Crossing> = self
+            .edges()
+            .filter(|(a, b)| (a.y <= y) != (b.y <= y))
+            .map(|(a, b)| crossing(a, b, y))
+            .collect();
+        crossings.sort_unstable();
+        // the stretches of the row covered by edges, sorted by where they start
+        let mut boundary: Vec<(i64, i64)> = self
+            .edges()
+            .filter(|(a, b)| (a.y.min(b.y)..=a.y.max(b.y)).contains(&y))
+            .filter_map(|(a, b)| {
+                if a.y == b.y {
+                    return Some((a.x.min(b.x), a.x.max(b.x)));
+                }
+                let c = crossing(a, b, y);
+                let x = i64::try_from(c.numerator / c.denominator).unwrap();
+                (c.numerator % c.denominator == 0).then_some((x, x))
+            })
+            .collect();
+        boundary.sort_unstable();
+
+        let (mut passed, mut covering) = (0, 0);
+        xs.iter()
+            .map(|x| {
+                while passed < crossings.len() && crossings[passed].is_left_of(*x) {
+                    passed += 1;
+                }
+                while covering < boundary.len() && boundary[covering].1 < *x {
+                    covering += 1;
+                }
+                if boundary.get(covering).is_some_and(|(start, _)| start <= x) {
+                    Location::Boundary
+                } else if passed % 2 == 1 {
+                    Location::Inside
+                } else {
+                    Location::Outside
+                }
+            })
+            .collect()
+    }
+
+    /// The cells of a `width` x `height` grid strictly inside the polygon.
+    /// Scans row by row, so it stays fast for long boundaries.
+    pub fn interior_mask(&self, width: usize, height: usize) -> Grid<bool> {
+        let columns: Vec<i64> = (0..width).map(|x| i64::try_from(x).unwrap()).collect();
+        let mut mask = Grid::new(width, height, false);
+        for y in 0..height {
+            let row = self.locate_row(i64::try_from(y).unwrap(), &columns);
+            for (x, location) in row.into_iter().enumerate() {
+                mask[&Point { x, y }] = location == Location::Inside;
+            }
+        }
+        mask
+    }
+}
+
+impl From<&Path> for Polygon {
+    /// Every point of the path becomes a vertex.
+    fn from(path: &Path) -> Self {
+        Self::new(
+            path.0
+                .iter()
+                .map(|p| Point {
+                    x: i64::try_from(p.x).unwrap(),
+                    y: i64::try_from(p.y).unwrap(),
+                })
+                .collect(),
+        )
+    }
+}
+
+fn on_segment(a: Point<i64>, b: Point<i64>, p: Point<i64>) -> bool {
+    let (ab, ap) = (b - a, p - a);
+    let cross = i128::from(ab.x) * i128::from(ap.y) - i128::from(ab.y) * i128::from(ap.x);
+    cross == 0
+        && (a.x.min(b.x)..=a.x.max(b.x)).contains(&p.x)
+        && (a.y.min(b.y)..=a.y.max(b.y)).contains(&p.y)
+}
+
+/// The x coordinate where an edge crosses a row, as a fraction with a
+/// positive denominator.
+#[derive(Debug, Clone, Copy)]
+struct Crossing {
+    numerator: i128,
+    denominator: i128,
+}
+
+fn crossing(a: Point<i64>, b: Point<i64>, y: i64) -> Crossing {
+    let (dx, dy) = (i128::from(b.x - a.x), i128::from(b.y - a.y));
+    let numerator = i128::from(a.x) * dy + i128::from(y - a.y) * dx;
+    if dy < 0 {
+        Crossing {
+            numerator: -numerator,
+            denominator: -dy,
+        }
+    } else {
+        Crossing {
+            numerator,
+            denominator: dy,
+        }
+    }
+}
+
+impl Crossing {
+    fn is_left_of(self, x: i64) -> bool {
+        self.numerator < i128::from(x) * self.denominator
+    }
+}
+
+impl Ord for Crossing {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl PartialOrd for Crossing {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Crossing {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Crossing {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pipe_loop() {
+        let corners = [
+            (1, 1),
+            (9, 1),
+            (9, 7),
+            (6, 7),
+            (6, 5),
+            (8, 5),
+            (8, 2),
+            (2, 2),
+            (2, 5),
+            (4, 5),
+            (4, 7),
+            (1, 7),
+        ];
+        let polygon = Polygon::new(
+            corners
+                .iter()
+                .map(|(x, y)| Point { x: *x, y: *y })
+                .collect(),
+        );
+        assert_eq!(polygon.boundary_points(), 46);
+        assert_eq!(polygon.interior_points(), 4);
+        assert_eq!(polygon.locate(&Point { x: 3, y: 6 }), Location::Inside);
+        assert_eq!(polygon.locate(&Point { x: 5, y: 6 }), Location::Outside);
+        assert_eq!(polygon.locate(&Point { x: 9, y: 4 }), Location::Boundary);
+
+        let xs = [0, 1, 3, 5, 7, 9];
+        assert_eq!(
+            polygon.locate_row(5, &xs),
+            xs.map(|x| polygon.locate(&Point { x, y: 5 }))
+        );
+        let triangle = Polygon::new(vec![
+            Point { x: 0, y: 0 },
+            Point { x: 4, y: 0 },
+            Point { x: 0, y: 4 },
+        ]);
+        assert_eq!(
+            triangle.locate_row(2, &[-1, 0, 1, 2, 3]),
+            [
+                Location::Outside,
+                Location::Boundary,
+                Location::Inside,
+                Location::Boundary,
+                Location::Outside
+            ]
+        );
+
+        let mask = polygon.interior_mask(11, 9);
+        assert_eq!(
+            mask.positions_of(&true).collect::<Vec<_>>(),
+            [
+                Point { x: 2, y: 6 },
+                Point { x: 3, y: 6 },
+                Point { x: 7, y: 6 },
+                Point { x: 8, y: 6 },
+            ]
+        );
+    }
+
+    #[test]
+    fn dig_plan() {
+        let plan = "R 6\nD 5\nL 2\nD 2\nR 2\nD 2\nL 5\nU 2\nL 1\nU 2\nR 2\nU 3\nL 2\nU 2\n";
+        let steps = plan.lines().map(|line| {
+            let (direction, length) = line.split_once(' ').unwrap();
+            let direction = match direction {
+                "R" => Vector { x: 1, y: 0 },
+                "D" => Vector { x: 0, y: 1 },
+                "L" => Vector { x: -1, y: 0 },
+                _ => Vector { x: 0, y: -1 },
+            };
+            (direction, length.parse().unwrap())
+        });
+        let polygon = Polygon::from_steps(Point { x: 0, y: 0 }, steps);
+        assert!(polygon.double_signed_area() > 0);
+        assert_eq!(polygon.boundary_points() + polygon.interior_points(), 62);
+    }
+
+    #[test]
+    fn degenerate() {
+        let line = Polygon::new(vec![Point { x: 0, y: 0 }, Point { x: 3, y: 0 }]);
+        assert_eq!(line.double_area(), 0);
+        assert_eq!(line.boundary_points(), 6);
+        assert_eq!(line.interior_points(), 0);
+        let collinear = Polygon::new(vec![
+            Point { x: 0, y: 0 },
+            Point { x: 2, y: 2 },
+            Point { x: 4, y: 4 },
+        ]);
+        assert_eq!(collinear.interior_points(), 0);
+        let empty = Polygon::new(Vec::new());
+        assert_eq!(empty.boundary_points(), 0);
+        assert_eq!(empty.interior_points(), 0);
+    }
+}
@@ -0,0 +1,252 @@
+use super::{BoundingBox, Grid, Neighborhood, Point};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub label: usize,
+    /// First cell of the region in reading order.
+    pub start: Point,
+    pub area: usize,
+    pub perimeter: usize,
+    /// Number of straight fence segments, which is the same as the number of corners.
+    pub sides: usize,
+    pub bounding_box: BoundingBox,
+    /// Enclosed pockets of other cells. The background is connected through the
+    /// neighborhood the region does not use, so for orthogonal regions a pocket
+    /// that leaks out diagonally is not a hole.
+    pub holes: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Labeling {
+    labels: Grid<usize>,
+    regions: Vec<Region>,
+    points: Vec<Vec<Point>>,
+}
+
+impl Labeling {
+    pub fn labels(&self) -> &Grid<usize> {
+        &self.labels
+    }
+
+    pub fn label(&self, point: &Point) -> Option<usize> {
+        self.labels.get(point).copied()
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn region_at(&self, point: &Point) -> Option<&Region> {
+        self.label(point).map(|label| &self.regions[label])
+    }
+
+    pub fn points(&self, label: usize) -> &[Point] {
+        &self.points[label]
+    }
+}
+
+// Window classification for Gray's bit quads, see `count_windows`.
+#[derive(Debug, Default)]
+struct Quads {
+    one: usize,
+    three: usize,
+    diagonal: usize,
+}
+
+impl Grid<usize> {
+    fn has_label(&self, x: usize, y: usize, label: usize) -> bool {
+        // coordinates are shifted by one so the row/column before the grid is 0
+        x > 0 && y > 0 && self.get(&Point { x: x - 1, y: y - 1 }) == Some(&label)
+    }
+
+    /// Classifies the 2x2 windows around the corners of a cell. Each window is
+    /// only counted from the first of its cells that carries the label, so
+    /// summing over all cells of a region counts every window exactly once.
+    fn count_windows(&self, point: &Point, quads: &mut Quads) {
+        let label = self[point];
+        // shifted coordinates of the cell, see `has_label`
+        let (x, y) = (point.x + 1, point.y + 1);
+        for (vx, vy) in [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)] {
+            let window = [(vx - 1, vy - 1), (vx, vy - 1), (vx - 1, vy), (vx, vy)];
+            let mask = window
+                .iter()
+                .enumerate()
+                .filter(|(_, (x, y))| self.has_label(*x, *y, label))
+                .fold(0_u8, |mask, (i, _)| mask | (1 << i));
+            let own = window.iter().position(|cell| *cell == (x, y)).unwrap();
+            if mask.trailing_zeros() as usize != own {
+                continue;
+            }
+            match mask.count_ones() {
+                1 => quads.one += 1,
+                3 => quads.three += 1,
+                2 if mask == 0b1001 || mask == 0b0110 => quads.diagonal += 1,
+                _ => (),
+            }
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Splits the grid into connected regions. Two neighboring cells belong to
+    /// the same region if `same` holds for them.
+    pub fn label_regions(
+        &self,
+        neighborhood: Neighborhood,
+        same: impl Fn(&T, &T) -> bool,
+    ) -> Labeling {
+        const UNLABELED: usize = usize::MAX;
+
+        let mut labels = Grid {
+            inner: vec![UNLABELED; self.inner.len()],
+            width: self.width,
+        };
+        let mut points = Vec::new();
+        let mut stack = Vec::new();
+
+        for i in 0..self.inner.len() {
+            if labels.inner[i] != UNLABELED {
+                continue;
+            }
+            let label = points.len();
+            let mut members = Vec::new();
+            labels.inner[i] = label;
+            stack.push(Point {
+                x: i % self.width,
+                y: i / self.width,
+            });
+            while let Some(point) = stack.pop() {
+                for neighbor in point.neighbors_in(neighborhood) {
+                    if labels.get(&neighbor) == Some(&UNLABELED)
+                        && self.get(&neighbor).is_some_and(|other| {
+                            same(&self.inner[point.y * self.width + point.x], other)
+                        })
+                    {
+                        labels.inner[neighbor.y * self.width + neighbor.x] = label;
+                        stack.push(neighbor);
+                    }
+                }
+                members.push(point);
+            }
+            points.push(members);
+        }
+
+        let regions = points
+            .iter()
+            .enumerate()
+            .map(|(label, members)| {
+                let mut quads = Quads::default();
+                let mut perimeter = 0;
+                for point in members {
+                    perimeter += 4 - point
+                        .neighbors_in(Neighborhood::Orthogonal)
+                        .filter(|neighbor| labels.get(neighbor) == Some(&label))
+                        .count();
+                    labels.count_windows(point, &mut quads);
+                }
+
+                // Euler number (components minus holes) from the bit quads. There
+                // is exactly one component, so the rest are holes.
+                let (one, three, diagonal) = (
+                    isize::try_from(quads.one).unwrap(),
+                    isize::try_from(quads.three).unwrap(),
+                    isize::try_from(quads.diagonal).unwrap(),
+                );
+                let euler = match neighborhood {
+                    Neighborhood::Orthogonal => (one - three + 2 * diagonal) / 4,
+                    Neighborhood::All => (one - three - 2 * diagonal) / 4,
+                };
+
+                Region {
+                    label,
+                    start: *members.iter().min_by_key(|p| (p.y, p.x)).unwrap(),
+                    area: members.len(),
+                    perimeter,
+                    sides: quads.one + quads.three + 2 * quads.diagonal,
+                    bounding_box: BoundingBox::from_points(members).unwrap(),
+                    holes: usize::try_from(1 - euler).unwrap(),
+                }
+            })
+            .collect();
+
+        Labeling {
+            labels,
+            regions,
+            points,
+        }
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: PartialEq,
+{
+    /// Orthogonally connected regions of equal cells.
+    pub fn regions(&self) -> Labeling {
+        self.label_regions(Neighborhood::Orthogonal, |a, b| a == b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE
+";
+
+    #[test]
+    fn fence_prices() {
+        let grid: Grid<char> = Grid::from_str(EXAMPLE);
+        let labeling = grid.regions();
+        assert_eq!(labeling.regions().len(), 11);
+        let by_perimeter: usize = labeling
+            .regions()
+            .iter()
+            .map(|r| r.area * r.perimeter)
+            .sum();
+        let by_sides: usize = labeling.regions().iter().map(|r| r.area * r.sides).sum();
+        assert_eq!(by_perimeter, 1930);
+        assert_eq!(by_sides, 1206);
+    }
+
+    #[test]
+    fn holes() {
+        let grid: Grid<char> = Grid::from_str("AAAAA\nABBBA\nABABA\nABBBA\nAAAAA\n");
+        let labeling = grid.regions();
+        assert_eq!(labeling.regions().len(), 3);
+        let ring = labeling.region_at(&Point { x: 1, y: 1 }).unwrap();
+        assert_eq!(
+            (ring.area, ring.perimeter, ring.sides, ring.holes),
+            (8, 16, 8, 1)
+        );
+        let outer = labeling.region_at(&Point { x: 0, y: 0 }).unwrap();
+        assert_eq!(outer.holes, 1);
+        assert_eq!(
+            outer.bounding_box,
+            BoundingBox {
+                min: Point { x: 0, y: 0 },
+                max: Point { x: 4, y: 4 }
+            }
+        );
+        assert_eq!(labeling.region_at(&Point { x: 2, y: 2 }).unwrap().holes, 0);
+    }
+
+    #[test]
+    fn diagonal_leak() {
+        let grid: Grid<char> = Grid::from_str("AAAAA\nABBAA\nABABA\nABBBA\nAAAAA\n");
+        let point = Point { x: 1, y: 1 };
+        let orthogonal = grid.regions();
+        assert_eq!(orthogonal.region_at(&point).unwrap().holes, 0);
+        let all = grid.label_regions(Neighborhood::All, |a, b| a == b);
+        assert_eq!(all.region_at(&point).unwrap().holes, 1);
+    }
+}
@@ -0,0 +1,448 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use super::{Grid, Path, Point};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S, C> {
+    pub cost: C,
+    /// All states from the start to the goal, both included.
+    pub states: Vec<S>,
+}
+
+impl<S, C> SearchResult<S, C> {
+    pub fn path_by(&self, f: impl Fn(&S) -> Point) -> Path {
+        Path(self.states.iter().map(f).collect())
+    }
+}
+
+impl<C> SearchResult<Point, C> {
+    pub fn path(&self) -> Path {
+        Path(self.states.clone())
+    }
+}
+
+// States are interned into a vec so the heap and the parent links only need
+// to deal with indices.
+struct Explored<S, C> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    costs: Vec<C>,
+    parents: Vec<Vec<usize>>,
+}
+
+impl<S, C> Explored<S, C>
+where
+    S: Clone + Eq + Hash,
+{
+    fn new(start: S, cost: C) -> Self {
+        Self {
+            index: HashMap::from([(start.clone(), 0)]),
+            states: vec![start],
+            costs: vec![cost],
+            parents: vec![Vec::new()],
+        }
+    }
+
+    fn intern(&mut self, state: S, cost: C) -> (usize, bool) {
+        if let Some(&i) = self.index.get(&state) {
+            (i, false)
+        } else {
+            let i = self.states.len();
+            self.index.insert(state.clone(), i);
+            self.states.push(state);
+            self.costs.push(cost);
+            self.parents.push(Vec::new());
+            (i, true)
+        }
+    }
+
+    fn trace(&self, mut i: usize) -> Vec<S> {
+        let mut states = vec![self.states[i].clone()];
+        while let Some(&parent) = self.parents[i].first() {
+            states.push(self.states[parent].clone());
+            i = parent;
+        }
+        states.reverse();
+        states
+    }
+}
+
+fn search<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+    mut heuristic: impl FnMut(&S) -> C,
+    all: bool,
+) -> Option<(Explored<S, C>, Vec<usize>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&start), C::default(), 0)));
+    let mut explored = Explored::new(start, C::default());
+    let mut closed = vec![false];
+    let mut goals = Vec::new();
+
+    while let Some(Reverse((estimate, cost, i))) = heap.pop() {
+        if closed[i] || cost > explored.costs[i] {
+            continue;
+        }
+        if let Some(&first) = goals.first() {
+            if estimate > explored.costs[first] {
+                break;
+            }
+        }
+        closed[i] = true;
+
+        if goal(&explored.states[i]) {
+            goals.push(i);
+            if !all {
+                break;
+            }
+            continue;
+        }
+
+        for (next, step) in successors(&explored.states[i].clone()) {
+            let next_cost = cost + step;
+            let (j, new) = explored.intern(next, next_cost);
+            // a state closed already can only be reached again at the same
+            // cost over zero cost steps, and linking back to it would make
+            // the parents cyclic
+            if new {
+                closed.push(false);
+            } else if next_cost > explored.costs[j]
+                || (next_cost == explored.costs[j] && (!all || closed[j]))
+            {
+                continue;
+            }
+            if new || next_cost < explored.costs[j] {
+                explored.costs[j] = next_cost;
+                explored.parents[j].clear();
+                closed[j] = false;
+                heap.push(Reverse((
+                    next_cost + heuristic(&explored.states[j]),
+                    next_cost,
+                    j,
+                )));
+            }
+            explored.parents[j].push(i);
+        }
+    }
+
+    if goals.is_empty() {
+        None
+    } else {
+        Some((explored, goals))
+    }
+}
+
+/// Breadth-first search where every step costs 1.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut explored = Explored::new(start, 0);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(i) = queue.pop_front() {
+        if goal(&explored.states[i]) {
+            return Some(SearchResult {
+                cost: explored.costs[i],
+                states: explored.trace(i),
+            });
+        }
+        let cost = explored.costs[i] + 1;
+        for next in successors(&explored.states[i].clone()) {
+            let (j, new) = explored.intern(next, cost);
+            if new {
+                explored.parents[j].push(i);
+                queue.push_back(j);
+            }
+        }
+    }
+    None
+}
+
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<SearchResult<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, goal, |_| C::default())
+}
+
+/// The heuristic must never overestimate the remaining cost, otherwise the
+/// result is not guaranteed to be optimal.
+pub fn astar<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+    heuristic: impl FnMut(&S) -> C,
+) -> Option<SearchResult<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (explored, goals) = search(start, successors, goal, heuristic, false)?;
+    Some(SearchResult {
+        cost: explored.costs[goals[0]],
+        states: explored.trace(goals[0]),
+    })
+}
+
+/// Like [`dijkstra`], but keeps every predecessor on a shortest path instead
+/// of just one, and every goal state reachable at the lowest cost.
+pub fn dijkstra_all<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<ShortestPaths<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_all(start, successors, goal, |_| C::default())
+}
+
+/// Like [`astar`], but keeps all shortest paths. This needs a consistent
+/// heuristic, i.e. one that never drops by more than the cost of a step.
+pub fn astar_all<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+    heuristic: impl FnMut(&S) -> C,
+) -> Option<ShortestPaths<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (explored, goals) = search(start, successors, goal, heuristic, true)?;
+    Some(ShortestPaths { explored, goals })
+}
+
+pub struct ShortestPaths<S, C> {
+    explored: Explored<S, C>,
+    goals: Vec<usize>,
+}
+
+impl<S, C> ShortestPaths<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy,
+{
+    pub fn cost(&self) -> C {
+        self.explored.costs[self.goals[0]]
+    }
+
+    pub fn goals(&self) -> impl Iterator<Item = &S> {
+        self.goals.iter().map(|&i| &self.explored.states[i])
+    }
+
+    pub fn predecessors(&self, state: &S) -> impl Iterator<Item = &S> {
+        self.explored
+            .index
+            .get(state)
+            .into_iter()
+            .flat_map(|&i| self.explored.parents[i].iter())
+            .map(|&i| &self.explored.states[i])
+    }
+
+    /// Every state that lies on at least one shortest path.
+    pub fn states_on_paths(&self) -> Vec<&S> {
+        let mut seen = vec![false; self.explored.states.len()];
+        let mut stack = self.goals.clone();
+        for &i in &stack {
+            seen[i] = true;
+        }
+        let mut result = Vec::new();
+        while let Some(i) = stack.pop() {
+            result.push(&self.explored.states[i]);
+            for &parent in &self.explored.parents[i] {
+                if !seen[parent] {
+                    seen[parent] = true;
+                    stack.push(parent);
+                }
+            }
+        }
+        result
+    }
+
+    /// Enumerates the shortest paths one by one, so there is no need to hold
+    /// all of them in memory at once.
+    pub fn paths(&self) -> impl Iterator<Item = Vec<S>> + use<'_, S, C> {
+        let mut stack: Vec<Vec<usize>> = self.goals.iter().map(|&i| vec![i]).collect();
+        std::iter::from_fn(move || {
+            while let Some(partial) = stack.pop() {
+                let last = *partial.last().unwrap();
+                let parents = &self.explored.parents[last];
+                if parents.is_empty() {
+                    return Some(
+                        partial
+                            .iter()
+                            .rev()
+                            .map(|&i| self.explored.states[i].clone())
+                            .collect(),
+                    );
+                }
+                for &parent in parents {
+                    let mut next = partial.clone();
+                    next.push(parent);
+                    stack.push(next);
+                }
+            }
+            None
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Shortest orthogonal walk between two cells, only stepping on cells for
+    /// which `passable` holds.
+    pub fn shortest_path(
+        &self,
+        start: &Point,
+        end: &Point,
+        passable: impl Fn(&T) -> bool,
+    ) -> Option<SearchResult<Point, usize>> {
+        bfs(
+            *start,
+            |point| {
+                self.neighbors_of(point)
+                    .filter(|neighbor| passable(neighbor.value))
+                    .map(|neighbor| neighbor.point)
+                    .collect::<Vec<Point>>()
+            },
+            |point| point == end,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::helpers::Vector;
+
+    const MAZE: &str = "#######
+#S....#
+#.###.#
+#.....#
+###.#E#
+#######
+";
+
+    #[test]
+    fn grid_bfs() {
+        let grid: Grid<char> = Grid::from_str(MAZE);
+        let result = grid
+            .shortest_path(&Point { x: 1, y: 1 }, &Point { x: 5, y: 4 }, |c| *c != '#')
+            .unwrap();
+        assert_eq!(result.cost, 7);
+        assert_eq!(result.path().0.len(), 8);
+        assert_eq!(result.states.first(), Some(&Point { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn composite_state() {
+        // turning costs 1000, moving costs 1
+        let grid: Grid<char> = Grid::from_str(MAZE);
+        let start = (Point { x: 1, y: 1 }, Vector { x: 1, y: 0 });
+        let end = Point { x: 5, y: 4 };
+        let successors = |(point, dir): &(Point, Vector)| {
+            let mut next = vec![
+                (
+                    (
+                        *point,
+                        Vector {
+                            x: -dir.y,
+                            y: dir.x,
+                        },
+                    ),
+                    1000,
+                ),
+                (
+                    (
+                        *point,
+                        Vector {
+                            x: dir.y,
+                            y: -dir.x,
+                        },
+                    ),
+                    1000,
+                ),
+            ];
+            if let Some(forward) = point
+                .checked_add(*dir)
+                .filter(|p| grid.get(p) != Some(&'#'))
+            {
+                next.push(((forward, *dir), 1));
+            }
+            next
+        };
+
+        let result = dijkstra(start, successors, |(point, _)| *point == end).unwrap();
+        assert_eq!(result.cost, 1007);
+        let heuristic = |(point, _): &(Point, Vector)| point.manhattan(end);
+        let result = astar(start, successors, |(point, _)| *point == end, heuristic).unwrap();
+        assert_eq!(result.cost, 1007);
+        assert_eq!(result.path_by(|(point, _)| *point).0.last(), Some(&end));
+
+        let all = dijkstra_all(start, successors, |(point, _)| *point == end).unwrap();
+        assert_eq!(all.cost(), 1007);
+        assert_eq!(all.paths().count(), 1);
+        assert_eq!(all.states_on_paths().len(), 9);
+    }
+
+    #[test]
+    fn multiple_shortest_paths() {
+        let grid: Grid<char> = Grid::from_str("...\n...\n...\n");
+        let end = Point { x: 2, y: 2 };
+        let all = dijkstra_all(
+            Point { x: 0, y: 0 },
+            |point| {
+                grid.neighbors_of(point)
+                    .map(|n| (n.point, 1))
+                    .collect::<Vec<_>>()
+            },
+            |point| *point == end,
+        )
+        .unwrap();
+        assert_eq!(all.cost(), 4);
+        assert_eq!(all.paths().count(), 6);
+        assert!(all.paths().all(|path| path.len() == 5));
+        assert_eq!(all.states_on_paths().len(), 9);
+    }
+
+    #[test]
+    fn zero_cost_loop() {
+        // 0 and 1 lead to each other for free, both go on to 2
+        let successors = |state: &u8| match state {
+            0 => vec![(1, 0), (2, 1)],
+            1 => vec![(0, 0), (2, 1)],
+            _ => Vec::new(),
+        };
+        let all = dijkstra_all(0, successors, |state| *state == 2).unwrap();
+        assert_eq!(all.cost(), 1);
+        assert_eq!(all.paths().collect::<Vec<_>>(), [vec![0, 1, 2], vec![0, 2]]);
+        assert_eq!(all.predecessors(&0).count(), 0);
+        assert_eq!(all.states_on_paths().len(), 3);
+    }
+}
@@ -0,0 +1,207 @@
+use super::rational::Rational;
+
+fn differences(values: &[i128]) -> Vec<i128> {
+    values
+        .windows(2)
+        .map(|pair| pair[1].checked_sub(pair[0]).expect("difference overflow"))
+        .collect()
+}
+
+/// The rows of repeated differences, down to the first row that is all zero.
+fn difference_table(values: &[i64]) -> Vec<Vec<i128>> {
+    assert!(!values.is_empty(), "need at least one value");
+    let mut table = vec![values.iter().map(|v| i128::from(*v)).collect::<Vec<_>>()];
+    while let Some(last) = table.last().filter(|row| row.iter().any(|d| *d != 0)) {
+        table.push(differences(last));
+    }
+    table
+}
+
+/// The value after the last one, assuming the sequence is a polynomial of a
+/// degree below its length.
+pub fn next_value(values: &[i64]) -> i128 {
+    difference_table(values)
+        .iter()
+        .filter_map(|row| row.last())
+        .try_fold(0_i128, |sum, last| sum.checked_add(*last))
+        .expect("extrapolation overflow")
+}
+
+/// The value before the first one, see [`next_value`].
+pub fn previous_value(values: &[i64]) -> i128 {
+    difference_table(values)
+        .iter()
+        .filter_map(|row| row.first())
+        .rev()
+        .try_fold(0_i128, |below, first| first.checked_sub(below))
+        .expect("extrapolation overflow")
+}
+
+/// Coefficients from the constant term up, with no trailing zeros.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial {
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<Rational>) -> Self {
+        while coefficients.last().is_some_and(Rational::is_zero) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    /// The polynomial of the lowest degree through all `(x, y)` points, by
+    /// Lagrange interpolation. The x values have to be distinct.
+    pub fn fit(points: &[(i64, i64)]) -> Self {
+        let mut coefficients = vec![Rational::ZERO; points.len()];
+        for (i, (xi, yi)) in points.iter().enumerate() {
+            // the basis is 1 at xi and 0 at every other x
+            let mut basis = vec![Rational::ONE];
+            let mut scale = Rational::from(*yi);
+            for (j, (xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                assert!(xi != xj, "x values have to be distinct");
+                // multiply by (x - xj)
+                basis.insert(0, Rational::ZERO);
+                for k in 0..basis.len() - 1 {
+                    let shifted = basis[k + 1] * Rational::from(*xj);
+                    basis[k] -= shifted;
+                }
+                scale /= Rational::from(*xi) - Rational::from(*xj);
+            }
+            for (coefficient, b) in coefficients.iter_mut().zip(basis) {
+                *coefficient += b * scale;
+            }
+        }
+        Self::new(coefficients)
+    }
+
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    /// `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn at(&self, x: Rational) -> Rational {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Rational::ZERO, |value, coefficient| {
+                value * x + *coefficient
+            })
+    }
+
+    /// The value at an integer, if that is an integer too.
+    pub fn at_integer(&self, x: i64) -> Option<i128> {
+        self.at(Rational::from(x)).to_integer()
+    }
+}
+
+/// The value at `x` of the polynomial through the points, see
+/// [`Polynomial::fit`].
+pub fn interpolate(points: &[(i64, i64)], x: i64) -> Rational {
+    Polynomial::fit(points).at(Rational::from(x))
+}
+
+/// A sequence that follows a polynomial in its index from `start` on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settled {
+    pub start: usize,
+    pub polynomial: Polynomial,
+}
+
+impl Settled {
+    /// The `n`th value of the sequence, for any `n` from `start` on. `None`
+    /// before `start`, where the sequence does not follow the polynomial.
+    pub fn at(&self, n: usize) -> Option<i128> {
+        if n < self.start {
+            return None;
+        }
+        self.polynomial.at_integer(i64::try_from(n).ok()?)
+    }
+}
+
+/// Finds where the sequence turns into a polynomial of at most `degree`,
+/// e.g. the plots reached after `65 + 131 * k` steps. The tail has to repeat
+/// the pattern twice more than needed to fit it, to rule out coincidences.
+pub fn settles_into(values: &[i64], degree: usize) -> Option<Settled> {
+    let mut row: Vec<i128> = values.iter().map(|v| i128::from(*v)).collect();
+    for _ in 0..=degree {
+        row = differences(&row);
+    }
+    let start = row.iter().rposition(|d| *d != 0).map_or(0, |i| i + 1);
+    if values.len() < start + degree + 3 {
+        return None;
+    }
+    let points: Vec<(i64, i64)> = (start..=start + degree)
+        .map(|n| (i64::try_from(n).unwrap(), values[n]))
+        .collect();
+    Some(Settled {
+        start,
+        polynomial: Polynomial::fit(&points),
+    })
+}
+
+/// [`settles_into`] a quadratic, the usual shape of growth on a tiled map.
+pub fn eventually_quadratic(values: &[i64]) -> Option<Settled> {
+    settles_into(values, 2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extrapolation() {
+        let histories: [&[i64]; 3] = [
+            &[0, 3, 6, 9, 12, 15],
+            &[1, 3, 6, 10, 15, 21],
+            &[10, 13, 16, 21, 30, 45],
+        ];
+        assert_eq!(histories.iter().map(|h| next_value(h)).sum::<i128>(), 114);
+        assert_eq!(histories.iter().map(|h| previous_value(h)).sum::<i128>(), 2);
+        assert_eq!(next_value(&[7]), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "extrapolation overflow")]
+    fn extrapolation_overflow() {
+        // every difference still fits, but their sum does not
+        let values: Vec<i64> = (0..65)
+            .map(|i| if i % 2 == 0 { i64::MAX } else { -i64::MAX })
+            .collect();
+        let _ = next_value(&values);
+    }
+
+    #[test]
+    fn interpolation() {
+        let triangle = Polynomial::fit(&[(0, 1), (1, 3), (2, 6)]);
+        assert_eq!(
+            triangle.coefficients(),
+            [Rational::ONE, Rational::new(3, 2), Rational::new(1, 2)]
+        );
+        assert_eq!(triangle.degree(), Some(2));
+        assert_eq!(triangle.at_integer(10), Some(66));
+        assert_eq!(interpolate(&[(2, 4), (4, 4)], 3), Rational::from(4_i64));
+        assert_eq!(Polynomial::fit(&[(1, 0), (5, 0)]).degree(), None);
+        assert_eq!(triangle.at(Rational::new(1, 2)), Rational::new(15, 8));
+    }
+
+    #[test]
+    fn eventual_growth() {
+        // 2n² + 3n + 1 after a noisy start
+        let values = [100, 0, 50, 28, 45, 66, 91, 120];
+        let settled = eventually_quadratic(&values).unwrap();
+        assert_eq!(settled.start, 3);
+        assert_eq!(settled.at(1_000_000), Some(2_000_003_000_001));
+        assert_eq!(settled.at(2), None);
+        assert_eq!(eventually_quadratic(&values[..6]), None);
+        assert_eq!(settles_into(&[1, 2, 4, 8, 16, 32], 2), None);
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Char(char),
+    Enter,
+    Escape,
+}
+
+/// Switches the terminal to unbuffered input without echo, using `stty` so
+/// there is no need for a terminal crate. The previous settings are restored
+/// on drop.
+pub struct RawMode {
+    saved: String,
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    } else {
+        Err(io::Error::other("stty failed, is stdin a terminal?"))
+    }
+}
+
+impl RawMode {
+    /// Reads block for at most a tenth of a second, so callers can animate
+    /// while waiting for keys.
+    pub fn enable() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "0", "time", "1"])?;
+        print!("\x1b[?25l");
+        io::stdout().flush()?;
+        Ok(Self { saved })
+    }
+
+    /// `None` if no key was pressed before the timeout. Takes `self` so keys
+    /// can only be read while raw mode is active.
+    #[allow(clippy::unused_self)]
+    pub fn read_key(&self) -> io::Result<Option<Key>> {
+        let mut buf = [0; 8];
+        let n = io::stdin().read(&mut buf)?;
+        Ok(parse_key(&buf[..n]))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+        print!("\x1b[?25h");
+        let _ = io::stdout().flush();
+    }
+}
+
+pub fn parse_key(bytes: &[u8]) -> Option<Key> {
+    match bytes {
+        [] => None,
+        [0x1b, b'[', b'A', ..] => Some(Key::Up),
+        [0x1b, b'[', b'B', ..] => Some(Key::Down),
+        [0x1b, b'[', b'C', ..] => Some(Key::Right),
+        [0x1b, b'[', b'D', ..] => Some(Key::Left),
+        [0x1b, ..] => Some(Key::Escape),
+        [b'\n' | b'\r', ..] => Some(Key::Enter),
+        _ => std::str::from_utf8(bytes)
+            .ok()?
+            .chars()
+            .next()
+            .map(Key::Char),
+    }
+}
+
+/// Moves the cursor to the top left and clears the screen.
+pub fn clear_screen(w: &mut impl Write) -> io::Result<()> {
+    write!(w, "\x1b[H\x1b[2J")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keys() {
+        assert_eq!(parse_key(b"\x1b[A"), Some(Key::Up));
+        assert_eq!(parse_key(b"\x1b[D"), Some(Key::Left));
+        // a second key read together with the first one is dropped
+        assert_eq!(parse_key(b"\x1b[Bq"), Some(Key::Down));
+        assert_eq!(parse_key(b"\x1b"), Some(Key::Escape));
+        assert_eq!(parse_key(b"\x1b["), Some(Key::Escape));
+        assert_eq!(parse_key(b"\r"), Some(Key::Enter));
+        assert_eq!(parse_key(b"q"), Some(Key::Char('q')));
+        assert_eq!(parse_key("é".as_bytes()), Some(Key::Char('é')));
+        // the first byte of a character cut off by the read
+        assert_eq!(parse_key(&"é".as_bytes()[..1]), None);
+        assert_eq!(parse_key(b""), None);
+    }
+}
@@ -0,0 +1,197 @@
+use super::{Grid, GridPoint, Path, Point};
+
+#[derive(Debug, Clone)]
+struct Memo {
+    count: usize,
+    /// Sorted indices of the reachable end cells.
+    endpoints: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum State {
+    Unvisited,
+    InProgress,
+    Done(Memo),
+}
+
+/// Counts the paths through a grid where each step has to satisfy a predicate,
+/// e.g. hiking trails that go up by exactly one every step. The steps have to
+/// form a DAG, any cycle causes a panic.
+///
+/// Results are memoized per cell, so asking for many sources only walks every
+/// cell once.
+pub struct Trails<'a, T, S, E> {
+    grid: &'a Grid<T>,
+    step: S,
+    end: E,
+    memo: Vec<State>,
+}
+
+impl<T> Grid<T> {
+    /// `step` decides if it is possible to go from the first to the second
+    /// (orthogonally adjacent) cell. Paths stop at cells for which `end` holds.
+    pub fn trails<S, E>(&self, step: S, end: E) -> Trails<'_, T, S, E>
+    where
+        S: Fn(&GridPoint<T>, &GridPoint<T>) -> bool,
+        E: Fn(&GridPoint<T>) -> bool,
+    {
+        Trails {
+            grid: self,
+            step,
+            end,
+            memo: vec![State::Unvisited; self.inner.len()],
+        }
+    }
+}
+
+impl<'a, T, S, E> Trails<'a, T, S, E>
+where
+    S: Fn(&GridPoint<T>, &GridPoint<T>) -> bool,
+    E: Fn(&GridPoint<T>) -> bool,
+{
+    fn index(&self, point: &Point) -> usize {
+        point.y * self.grid.width + point.x
+    }
+
+    fn point(&self, index: usize) -> Point {
+        Point {
+            x: index % self.grid.width,
+            y: index / self.grid.width,
+        }
+    }
+
+    fn grid_point(&self, point: Point) -> GridPoint<'a, T> {
+        GridPoint {
+            value: self.grid.get(&point).unwrap(),
+            point,
+        }
+    }
+
+    fn next_steps(&self, index: usize) -> impl Iterator<Item = Point> + use<'a, '_, T, S, E> {
+        let from = self.grid_point(self.point(index));
+        let is_end = (self.end)(&from);
+        self.grid
+            .neighbors_of(&from.point)
+            .filter(move |to| !is_end && (self.step)(&from, to))
+            .map(|to| to.point)
+    }
+
+    fn solve(&mut self, source: &Point) -> &Memo {
+        let source = self.index(source);
+        let mut stack = vec![(source, false)];
+        while let Some((i, children_done)) = stack.pop() {
+            if children_done {
+                let mut count = 0;
+                let mut endpoints = Vec::new();
+                if (self.end)(&self.grid_point(self.point(i))) {
+                    count = 1;
+                    endpoints.push(i);
+                }
+                for next in self.next_steps(i) {
+                    let State::Done(memo) = &self.memo[self.index(&next)] else {
+                        unreachable!("successors are solved first")
+                    };
+                    count += memo.count;
+                    endpoints.extend(&memo.endpoints);
+                }
+                endpoints.sort_unstable();
+                endpoints.dedup();
+                self.memo[i] = State::Done(Memo { count, endpoints });
+                continue;
+            }
+
+            match self.memo[i] {
+                State::Done(_) => continue,
+                State::InProgress => panic!("steps contain a cycle at {:?}", self.point(i)),
+                State::Unvisited => (),
+            }
+            self.memo[i] = State::InProgress;
+            stack.push((i, true));
+            let pending = self
+                .next_steps(i)
+                .map(|next| self.index(&next))
+                .filter(|&j| !matches!(self.memo[j], State::Done(_)))
+                .collect::<Vec<usize>>();
+            for j in pending {
+                if matches!(self.memo[j], State::InProgress) {
+                    panic!("steps contain a cycle at {:?}", self.point(j));
+                }
+                stack.push((j, false));
+            }
+        }
+
+        let State::Done(memo) = &self.memo[source] else {
+            unreachable!()
+        };
+        memo
+    }
+
+    /// Number of distinct paths from `source` to any end cell.
+    pub fn count(&mut self, source: &Point) -> usize {
+        self.solve(source).count
+    }
+
+    /// All end cells that are reachable from `source`.
+    pub fn endpoints(&mut self, source: &Point) -> Vec<Point> {
+        let endpoints = self.solve(source).endpoints.clone();
+        endpoints.into_iter().map(|i| self.point(i)).collect()
+    }
+
+    /// Lazily enumerates all paths from `source`, both ends included. Branches
+    /// that do not lead to an end cell are never entered.
+    pub fn paths(&mut self, source: &Point) -> impl Iterator<Item = Path> + use<'a, '_, T, S, E> {
+        self.solve(source);
+        let mut stack = vec![vec![*source]];
+        std::iter::from_fn(move || {
+            while let Some(path) = stack.pop() {
+                let last = self.index(path.last().unwrap());
+                if (self.end)(&self.grid_point(self.point(last))) {
+                    return Some(Path(path));
+                }
+                for next in self.next_steps(last) {
+                    if matches!(&self.memo[self.index(&next)], State::Done(memo) if memo.count > 0)
+                    {
+                        let mut path = path.clone();
+                        path.push(next);
+                        stack.push(path);
+                    }
+                }
+            }
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    #[test]
+    fn hiking_trails() {
+        let grid = Grid::from_str_as_digits(EXAMPLE);
+        let mut trails = grid.trails(
+            |from, to| *to.value == from.value + 1,
+            |cell| *cell.value == 9,
+        );
+        let trailheads: Vec<Point> = grid.positions_of(&0).collect();
+
+        let score: usize = trailheads.iter().map(|p| trails.endpoints(p).len()).sum();
+        let rating: usize = trailheads.iter().map(|p| trails.count(p)).sum();
+        assert_eq!(score, 36);
+        assert_eq!(rating, 81);
+
+        let paths: Vec<Path> = trails.paths(&trailheads[0]).collect();
+        assert_eq!(paths.len(), 20);
+        assert!(paths.iter().all(|path| path.0.len() == 10));
+    }
+}
@@ -0,0 +1,202 @@
+use super::bitgrid::BitGrid;
+use super::{Grid, Point, Vector};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Agent {
+    pub position: Point,
+    /// Has to be one of [`Vector::ALL_DIRECTIONS`].
+    pub direction: Vector,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// One step in the current direction.
+    Move,
+    TurnRight,
+    TurnLeft,
+    Face(Vector),
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The agent tried to move out of the grid.
+    Exited,
+    /// The rule returned [`Action::Stop`].
+    Stopped,
+    /// The agent reached a state it has been in before. `start` is the index of
+    /// that state in [`Walk::states`], the loop is made up of the `length`
+    /// states from there up to the end.
+    Loop { start: usize, length: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct Walk {
+    pub outcome: Outcome,
+    states: Vec<Agent>,
+    visited: BitGrid,
+}
+
+impl Walk {
+    /// Every distinct (position, direction) the agent was in, in order.
+    pub fn states(&self) -> &[Agent] {
+        &self.states
+    }
+
+    pub fn loop_states(&self) -> &[Agent] {
+        match self.outcome {
+            Outcome::Loop { start, .. } => &self.states[start..],
+            Outcome::Exited | Outcome::Stopped => &[],
+        }
+    }
+
+    pub fn has_visited(&self, point: &Point) -> bool {
+        self.visited.get(point)
+    }
+
+    pub fn visited(&self) -> impl Iterator<Item = Point> + use<'_> {
+        self.visited.iter_ones()
+    }
+
+    pub fn visited_count(&self) -> usize {
+        self.visited.count_ones()
+    }
+
+    pub fn is_loop(&self) -> bool {
+        matches!(self.outcome, Outcome::Loop { .. })
+    }
+}
+
+fn direction_index(direction: &Vector) -> usize {
+    Vector::ALL_DIRECTIONS
+        .iter()
+        .position(|d| d == direction)
+        .unwrap_or_else(|| panic!("{direction:?} is not a unit direction"))
+}
+
+impl<T> Grid<T> {
+    /// Lets an agent walk through the grid. Before every step, `rule` gets the
+    /// agent and the cell in front of it (`None` outside of the grid) and
+    /// decides what to do next. Starting outside of the grid exits at once.
+    pub fn walk(&self, start: Agent, mut rule: impl FnMut(&Agent, Option<&T>) -> Action) -> Walk {
+        let mut visited = BitGrid::new(self.width(), self.height());
+        // one bit per cell and direction, the index into `states` is only
+        // looked up once a state repeats
+        let mut seen: [BitGrid; Vector::<isize>::ALL_DIRECTIONS.len()] =
+            std::array::from_fn(|_| visited.clone());
+        let mut states = Vec::new();
+        let mut agent = start;
+
+        let outcome = loop {
+            if !self.contains(&agent.position) {
+                break Outcome::Exited;
+            }
+            if !seen[direction_index(&agent.direction)].insert(&agent.position) {
+                let start = states.iter().position(|state| *state == agent).unwrap();
+                break Outcome::Loop {
+                    start,
+                    length: states.len() - start,
+                };
+            }
+            visited.set(&agent.position, true);
+            states.push(agent.clone());
+
+            let ahead = agent.position.checked_add(agent.direction);
+            match rule(&agent, ahead.as_ref().and_then(|p| self.get(p))) {
+                Action::Move => match ahead.filter(|p| self.contains(p)) {
+                    Some(position) => agent.position = position,
+                    None => break Outcome::Exited,
+                },
+                Action::TurnRight => agent.direction = agent.direction.turn_right(),
+                Action::TurnLeft => agent.direction = agent.direction.turn_left(),
+                Action::Face(direction) => agent.direction = direction,
+                Action::Stop => break Outcome::Stopped,
+            }
+        };
+
+        Walk {
+            outcome,
+            states,
+            visited,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    fn guard(grid: &Grid<char>, obstruction: Option<&Point>) -> Walk {
+        let start = Agent {
+            position: grid.find(|c| *c == '^').unwrap(),
+            direction: Vector { x: 0, y: -1 },
+        };
+        grid.walk(start, |agent, ahead| {
+            let blocked = obstruction
+                .is_some_and(|o| agent.position.checked_add(agent.direction).as_ref() == Some(o));
+            if blocked || ahead == Some(&'#') {
+                Action::TurnRight
+            } else {
+                Action::Move
+            }
+        })
+    }
+
+    #[test]
+    fn guard_patrol() {
+        let grid: Grid<char> = Grid::from_str(EXAMPLE);
+        let walk = guard(&grid, None);
+        assert_eq!(walk.outcome, Outcome::Exited);
+        assert_eq!(walk.visited_count(), 41);
+
+        let loops = walk
+            .visited()
+            .filter(|p| grid[p] == '.')
+            .filter(|p| guard(&grid, Some(p)).is_loop())
+            .count();
+        assert_eq!(loops, 6);
+    }
+
+    #[test]
+    fn loop_detection() {
+        let grid: Grid<char> = Grid::from_str("...\n...\n...\n");
+        let start = Agent {
+            position: Point { x: 0, y: 0 },
+            direction: Vector { x: 1, y: 0 },
+        };
+        let walk = grid.walk(start, |_, ahead| match ahead {
+            Some(_) => Action::Move,
+            None => Action::TurnRight,
+        });
+        // 8 border cells, plus a turn in each corner
+        assert_eq!(
+            walk.outcome,
+            Outcome::Loop {
+                start: 0,
+                length: 12
+            }
+        );
+        assert_eq!(walk.visited_count(), 8);
+        assert_eq!(walk.loop_states().len(), 12);
+
+        let outside = Agent {
+            position: Point { x: 3, y: 0 },
+            direction: Vector { x: 1, y: 0 },
+        };
+        let walk = grid.walk(outside, |_, _| Action::Move);
+        assert_eq!(walk.outcome, Outcome::Exited);
+        assert!(walk.states().is_empty());
+    }
+}
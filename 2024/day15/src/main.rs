@@ -5,23 +5,30 @@ mod puzzle;
 pub use output::Output;
 
 fn main() {
-    let input = puzzle::parse(&std::fs::read_to_string("input").expect("input could not be read"));
+    // only read what the mode needs, so the others work without `input`
+    let read = |path: &str| {
+        puzzle::parse(&std::fs::read_to_string(path).expect("input could not be read"))
+    };
 
     match std::env::args().nth(1) {
-        Some(s) if s == "1" => println!("part 1: {}", puzzle::part_1(&input)),
-        Some(s) if s == "2" => println!("part 2: {}", puzzle::part_2(&input)),
-        Some(s) if s == "example2" => println!("example2: {}", {
-            let input = puzzle::parse(
-                &std::fs::read_to_string("example2").expect("input could not be read"),
-            );
-            puzzle::part_2(&input)
-        }),
+        Some(s) if s == "1" => println!("part 1: {}", puzzle::part_1(&read("input"))),
+        Some(s) if s == "2" => println!("part 2: {}", puzzle::part_2(&read("input"))),
+        Some(s) if s == "example2" => println!("example2: {}", puzzle::part_2(&read("example2"))),
+        // drive the robot by hand, optionally in another input file
+        Some(s) if s == "tui" => {
+            let path = std::env::args()
+                .nth(2)
+                .unwrap_or_else(|| "input".to_owned());
+            helpers::tui::run(puzzle::Warehouse::new(&read(&path))).expect("terminal failed");
+        }
         _ => panic!("specify part"),
     }
 }
 
 #[cfg(test)]
 mod test {
+    use super::helpers::terminal::Key;
+    use super::helpers::tui::Session;
     use super::puzzle;
 
     fn example_input() -> puzzle::Input {
@@ -41,4 +48,43 @@ mod test {
             assert_eq!(puzzle::part_2(&example_input()), *output);
         }
     }
+
+    #[test]
+    fn warehouse_by_hand() {
+        let input = puzzle::parse(&std::fs::read_to_string("example2").unwrap());
+        let mut session = Session::new(puzzle::Warehouse::new(&input));
+        let start = session.screen();
+
+        let row = |session: &Session<puzzle::Warehouse>, y| {
+            session.screen().lines().nth(y).unwrap()[..16].to_owned()
+        };
+
+        // walking into the wall changes nothing
+        session.handle(Key::Left);
+        assert_eq!(session.steps, 0);
+
+        session.handle(Key::Right);
+        session.handle(Key::Right);
+        assert_eq!(row(&session, 3), "##..@[].......##");
+        // pushes the box below into the empty row under it
+        session.handle(Key::Down);
+        assert_eq!(row(&session, 4), "##..@.[]......##");
+        assert_eq!(row(&session, 5), "##..[]........##");
+        assert_eq!(session.steps, 3);
+
+        for _ in 0..3 {
+            session.handle(Key::Char('u'));
+        }
+        assert_eq!(session.steps, 0);
+        assert_eq!(session.screen(), start);
+
+        // replaying the input moves matches part 2
+        session.handle(Key::Char(' '));
+        while session.running {
+            session.tick();
+        }
+        assert!(session.finished);
+        let gps = format!("gps sum: {}", puzzle::part_2(&input));
+        assert!(session.screen().contains(&gps));
+    }
 }
@@ -1,3 +1,7 @@
+use std::rc::Rc;
+
+use crate::helpers::terminal::Key;
+use crate::helpers::tui::Simulation;
 use crate::helpers::{Grid, Point, Vector};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,7 +40,7 @@ impl std::fmt::Display for Cell {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum WideCell {
+pub enum WideCell {
     Wall,
     Robot,
     BoxStart,
@@ -112,48 +116,22 @@ pub fn parse(input: &str) -> Input {
 
 pub fn part_1(input: &Input) -> crate::Output {
     let mut grid = input.grid.clone();
-    let mut robot = grid.iter().find(|(_p, c)| *c == Cell::Robot).unwrap().0;
+    let mut robot = grid.find(|c| *c == Cell::Robot).unwrap();
 
     for m in &input.moves {
         let v: Vector = (*m).into();
-        let mut boxes_to_move = vec![];
-        let mut movable = false;
-        for i in 1.. {
-            let v = v.mul(i);
-            let pos = robot.add(&v).unwrap();
-            match grid.get(&pos).unwrap() {
-                Cell::Wall => {
-                    movable = false;
-                    break;
-                }
-                Cell::Robot => panic!("wtf"),
-                Cell::Box => {
-                    boxes_to_move.push(pos);
-                }
-                Cell::Empty => {
-                    movable = true;
-                    break;
-                }
-            }
-        }
-        if movable {
-            if !boxes_to_move.is_empty() {
-                *grid
-                    .get_mut(
-                        &robot
-                            .add(&v.mul(isize::try_from(boxes_to_move.len()).unwrap() + 1))
-                            .unwrap(),
-                    )
-                    .unwrap() = Cell::Box;
-            }
+        let (free, cell) = grid.first_hit(&robot, v, |c| *c != Cell::Box).unwrap();
+        if *cell == Cell::Empty {
+            // the first box moves to the end of the run
+            grid[&free] = Cell::Box;
             *grid.get_mut(&robot).unwrap() = Cell::Empty;
-            robot = robot.add(&v).unwrap();
+            robot = robot.checked_add(v).unwrap();
             *grid.get_mut(&robot).unwrap() = Cell::Robot;
         }
     }
     grid.iter()
         .filter_map(|(pos, cell)| {
-            if cell == Cell::Box {
+            if *cell == Cell::Box {
                 Some(100 * pos.y + pos.x)
             } else {
                 None
@@ -165,19 +143,19 @@ pub fn part_1(input: &Input) -> crate::Output {
 
 fn can_push_box_line(grid: &Grid<WideCell>, pos: &[&Point], dir: Move) -> bool {
     for pos in pos {
-        let next = &pos.add(&dir.into()).unwrap();
+        let next = &pos.checked_add(dir.into()).unwrap();
         let can_push: bool = match (dir, grid.get(next).unwrap()) {
             (_, WideCell::Wall) => false,
             (_, WideCell::Empty) => true,
             (_, WideCell::Robot) => panic!("wtf"),
             (Move::Up | Move::Down, WideCell::BoxStart) => {
-                let box_end = next.add(&Vector { x: 1, y: 0 }).unwrap();
+                let box_end = next.checked_add(Vector { x: 1, y: 0 }).unwrap();
                 assert!(matches!(*grid.get(&box_end).unwrap(), WideCell::BoxEnd));
 
                 can_push_box_line(grid, &[next, &box_end], dir)
             }
             (Move::Up | Move::Down, WideCell::BoxEnd) => {
-                let box_start = next.add(&Vector { x: -1, y: 0 }).unwrap();
+                let box_start = next.checked_add(Vector { x: -1, y: 0 }).unwrap();
                 assert!(matches!(*grid.get(&box_start).unwrap(), WideCell::BoxStart));
 
                 can_push_box_line(grid, &[&box_start, next], dir)
@@ -186,12 +164,12 @@ fn can_push_box_line(grid: &Grid<WideCell>, pos: &[&Point], dir: Move) -> bool {
                 panic!("invalid push check horizontal")
             }
             (Move::Left, WideCell::BoxEnd) => {
-                let box_start = next.add(&Vector { x: -1, y: 0 }).unwrap();
+                let box_start = next.checked_add(Vector { x: -1, y: 0 }).unwrap();
                 assert!(matches!(*grid.get(&box_start).unwrap(), WideCell::BoxStart));
                 can_push_box_line(grid, &[&box_start], dir)
             }
             (Move::Right, WideCell::BoxStart) => {
-                let box_end = next.add(&Vector { x: 1, y: 0 }).unwrap();
+                let box_end = next.checked_add(Vector { x: 1, y: 0 }).unwrap();
                 assert!(matches!(*grid.get(&box_end).unwrap(), WideCell::BoxEnd));
                 can_push_box_line(grid, &[&box_end], dir)
             }
@@ -218,19 +196,19 @@ fn push_column(
 
     let mut cells = Vec::new();
     for i in 0.. {
-        let posi = pos.add(&basev.mul(i)).unwrap();
+        let posi = pos.checked_add(basev.mul(i)).unwrap();
         let cell = *grid.get(&posi).unwrap();
-        moved.push(posi.clone());
+        moved.push(posi);
         if !matches!(cell, WideCell::BoxStart | WideCell::BoxEnd) {
             break;
         }
         match cell {
             WideCell::BoxStart => {
-                let box_end = posi.add(&Vector { x: 1, y: 0 }).unwrap();
+                let box_end = posi.checked_add(Vector { x: 1, y: 0 }).unwrap();
                 push_column(grid, &box_end, dir, moved, WideCell::Empty);
             }
             WideCell::BoxEnd => {
-                let box_start = posi.add(&Vector { x: -1, y: 0 }).unwrap();
+                let box_start = posi.checked_add(Vector { x: -1, y: 0 }).unwrap();
                 push_column(grid, &box_start, dir, moved, WideCell::Empty);
             }
             _ => panic!("wtf"),
@@ -242,7 +220,7 @@ fn push_column(
     for (i, cell) in cells.iter().enumerate() {
         *grid
             .get_mut(
-                &pos.add(&basev.mul(isize::try_from(i).unwrap().checked_add(1).unwrap()))
+                &pos.checked_add(basev.mul(isize::try_from(i).unwrap().checked_add(1).unwrap()))
                     .unwrap(),
             )
             .unwrap() = *cell;
@@ -259,7 +237,7 @@ fn push_box_vert(grid: &mut Grid<WideCell>, pos: &Point, dir: Move) {
             WideCell::Robot => {
                 push_column(
                     grid,
-                    &pos.add(&dir.into()).unwrap(),
+                    &pos.checked_add(dir.into()).unwrap(),
                     dir,
                     &mut Vec::new(),
                     WideCell::Robot,
@@ -271,7 +249,7 @@ fn push_box_vert(grid: &mut Grid<WideCell>, pos: &Point, dir: Move) {
 }
 
 fn push_box(grid: &mut Grid<WideCell>, pos: &Point, dir: Move, current: WideCell) {
-    let next = &pos.add(&dir.into()).unwrap();
+    let next = &pos.checked_add(dir.into()).unwrap();
     match (dir, grid.get(next).unwrap()) {
         (_, WideCell::Wall) => panic!("cannot push"),
         (_, WideCell::Robot) => panic!("wtf"),
@@ -285,14 +263,14 @@ fn push_box(grid: &mut Grid<WideCell>, pos: &Point, dir: Move, current: WideCell
             panic!("invalid push horizontal with box")
         }
         (Move::Left, WideCell::BoxEnd) => {
-            let box_start = next.add(&Vector { x: -1, y: 0 }).unwrap();
+            let box_start = next.checked_add(Vector { x: -1, y: 0 }).unwrap();
             assert!(matches!(*grid.get(&box_start).unwrap(), WideCell::BoxStart));
             push_box(grid, &box_start, dir, WideCell::BoxStart);
             *grid.get_mut(&box_start).unwrap() = WideCell::BoxEnd;
             *grid.get_mut(next).unwrap() = current;
         }
         (Move::Right, WideCell::BoxStart) => {
-            let box_end = next.add(&Vector { x: 1, y: 0 }).unwrap();
+            let box_end = next.checked_add(Vector { x: 1, y: 0 }).unwrap();
             assert!(matches!(*grid.get(&box_end).unwrap(), WideCell::BoxEnd));
             push_box(grid, &box_end, dir, WideCell::BoxEnd);
             *grid.get_mut(&box_end).unwrap() = WideCell::BoxStart;
@@ -301,36 +279,97 @@ fn push_box(grid: &mut Grid<WideCell>, pos: &Point, dir: Move, current: WideCell
     }
 }
 
-pub fn part_2(input: &Input) -> crate::Output {
-    let mut grid = input.grid.flat_transform(|cell| match cell {
-        Cell::Wall => [WideCell::Wall, WideCell::Wall],
-        Cell::Robot => [WideCell::Robot, WideCell::Empty],
-        Cell::Box => [WideCell::BoxStart, WideCell::BoxEnd],
-        Cell::Empty => [WideCell::Empty, WideCell::Empty],
-    });
+/// The wide warehouse of part 2. Stepping replays the moves from the input,
+/// and in the terminal the arrow keys move the robot by hand. The moves are
+/// shared, so an undo snapshot only copies the grid.
+#[derive(Debug, Clone)]
+pub struct Warehouse {
+    grid: Grid<WideCell>,
+    robot: Point,
+    moves: Rc<[Move]>,
+    next_move: usize,
+}
 
-    let mut robot = grid.iter().find(|(_p, c)| *c == WideCell::Robot).unwrap().0;
+impl Warehouse {
+    pub fn new(input: &Input) -> Self {
+        let grid = input.grid.flat_transform(|cell| match cell {
+            Cell::Wall => [WideCell::Wall, WideCell::Wall],
+            Cell::Robot => [WideCell::Robot, WideCell::Empty],
+            Cell::Box => [WideCell::BoxStart, WideCell::BoxEnd],
+            Cell::Empty => [WideCell::Empty, WideCell::Empty],
+        });
+        let robot = grid.find(|c| *c == WideCell::Robot).unwrap();
+        Self {
+            grid,
+            robot,
+            moves: input.moves.as_slice().into(),
+            next_move: 0,
+        }
+    }
 
-    for m in &input.moves {
-        let movable = can_push_box_line(&grid, &[&robot], *m);
-        if movable {
-            push_box(&mut grid, &robot, *m, WideCell::Robot);
-            *grid.get_mut(&robot).unwrap() = WideCell::Empty;
-            robot = robot.add(&(*m).into()).unwrap();
-            *grid.get_mut(&robot).unwrap() = WideCell::Robot;
+    /// Moves the robot and the boxes in front of it, unless they are stuck.
+    fn apply(&mut self, m: Move) -> bool {
+        if !can_push_box_line(&self.grid, &[&self.robot], m) {
+            return false;
         }
+        push_box(&mut self.grid, &self.robot, m, WideCell::Robot);
+        *self.grid.get_mut(&self.robot).unwrap() = WideCell::Empty;
+        self.robot = self.robot.checked_add(m.into()).unwrap();
+        *self.grid.get_mut(&self.robot).unwrap() = WideCell::Robot;
+        true
     }
 
-    grid.iter()
-        .filter_map(|(pos, cell)| {
-            if cell == WideCell::BoxStart {
-                Some(100 * pos.y + pos.x)
-            } else {
-                None
-            }
-        })
-        .sum::<usize>()
-        .into()
+    fn gps_sum(&self) -> usize {
+        self.grid
+            .iter()
+            .filter(|(_, cell)| **cell == WideCell::BoxStart)
+            .map(|(pos, _)| 100 * pos.y + pos.x)
+            .sum()
+    }
+}
+
+impl Simulation for Warehouse {
+    type Cell = WideCell;
+
+    fn step(&mut self) -> bool {
+        let Some(m) = self.moves.get(self.next_move).copied() else {
+            return false;
+        };
+        self.apply(m);
+        self.next_move += 1;
+        true
+    }
+
+    fn input(&mut self, key: Key) -> bool {
+        let m = match key {
+            Key::Up => Move::Up,
+            Key::Down => Move::Down,
+            Key::Left => Move::Left,
+            Key::Right => Move::Right,
+            _ => return false,
+        };
+        self.apply(m)
+    }
+
+    fn render(&self) -> Grid<WideCell> {
+        self.grid.clone()
+    }
+
+    fn counters(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "moves left".to_owned(),
+                (self.moves.len() - self.next_move).to_string(),
+            ),
+            ("gps sum".to_owned(), self.gps_sum().to_string()),
+        ]
+    }
+}
+
+pub fn part_2(input: &Input) -> crate::Output {
+    let mut warehouse = Warehouse::new(input);
+    while warehouse.step() {}
+    warehouse.gps_sum().into()
 }
 
 #[cfg(test)]
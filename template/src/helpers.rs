@@ -1,103 +1,505 @@
 #![allow(dead_code)]
 
+use std::collections::HashSet;
 use std::fmt::Display;
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Point {
-    pub x: usize,
-    pub y: usize,
+use coordinate::{Coordinate, Signed};
+
+pub mod automaton;
+pub mod bitgrid;
+pub mod compress;
+pub mod coordinate;
+pub mod digits;
+pub mod hex;
+pub mod image;
+pub mod linear;
+pub mod number;
+pub mod parse;
+pub mod pattern;
+pub mod polygon;
+pub mod rational;
+pub mod ray;
+pub mod recorder;
+pub mod regions;
+pub mod render;
+pub mod search;
+pub mod sequence;
+pub mod space;
+pub mod terminal;
+pub mod trails;
+pub mod tui;
+pub mod walker;
+
+/// Adding a [`Vector`] with `+` panics if the result is not a valid point,
+/// while [`Point::checked_add`] returns `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T = usize> {
+    pub x: T,
+    pub y: T,
 }
 
-#[derive(Debug, Clone)]
-pub struct PointCloud(Vec<Point>);
+/// A set of points with constant time membership. Iteration order is
+/// arbitrary, use [`PointCloud::sorted`] for reading order.
+#[derive(Debug, Clone, Default)]
+pub struct PointCloud<T = usize>(HashSet<Point<T>>);
 
-impl PointCloud {
-    pub fn from(v: Vec<Point>) -> Self {
-        Self(v)
+impl<T> PointCloud<T>
+where
+    T: Coordinate,
+{
+    pub fn new() -> Self {
+        Self(HashSet::new())
+    }
+
+    pub fn from(v: Vec<Point<T>>) -> Self {
+        Self(v.into_iter().collect())
     }
 
+    /// The number of points.
     pub fn area(&self) -> usize {
         self.0.len()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &Point> {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Point<T>> {
         self.0.iter()
     }
 
-    pub fn into_iter(self) -> impl Iterator<Item = Point> {
+    pub fn into_iter(self) -> impl Iterator<Item = Point<T>> {
         self.0.into_iter()
     }
 
+    /// Sorted by y, then x.
+    pub fn sorted(&self) -> Vec<Point<T>> {
+        let mut points: Vec<Point<T>> = self.0.iter().copied().collect();
+        points.sort_unstable_by_key(|p| (p.y, p.x));
+        points
+    }
+
+    /// Returns whether the point was new.
+    pub fn insert(&mut self, point: Point<T>) -> bool {
+        self.0.insert(point)
+    }
+
+    pub fn remove(&mut self, point: &Point<T>) -> bool {
+        self.0.remove(point)
+    }
+
     pub fn extend(&mut self, other: Self) {
         self.0.extend(other.0);
     }
 
-    pub fn contains(&self, point: &Point) -> bool {
-        self.0.iter().any(|p| p == point)
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        self.0.contains(point)
     }
 
-    pub fn into_bool_grid(self) -> Grid<bool> {
-        let mut points = self.0;
-        points.sort_by_key(|p| (p.y, p.x));
-        let min_x = points.iter().map(|p| p.x).min().unwrap();
-        let min_y = points.iter().map(|p| p.y).min().unwrap();
-        let max_x = points.iter().map(|p| p.x).max().unwrap();
-        let max_y = points.iter().map(|p| p.y).max().unwrap();
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0.union(&other.0).copied().collect())
+    }
 
-        let mut points = points.into_iter();
-        let mut next = points.next();
-        let mut v = Vec::new();
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                if next.as_ref().is_some_and(|p| *p == Point { x, y }) {
-                    v.push(true);
-                    next = points.next();
-                } else {
-                    v.push(false);
-                }
-            }
-        }
-        Grid {
-            inner: v,
-            width: max_x - min_x + 1,
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0.intersection(&other.0).copied().collect())
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(self.0.difference(&other.0).copied().collect())
+    }
+
+    /// `None` if a point would leave the range of `T`.
+    pub fn translate(&self, v: Vector<T::Delta>) -> Option<Self> {
+        self.0.iter().map(|p| p.checked_add(v)).collect()
+    }
+
+    /// Moves the points so the bounding box starts at the origin.
+    pub fn normalized(&self) -> Self {
+        let Some(bbox) = self.bounding_box() else {
+            return Self::new();
+        };
+        self.0
+            .iter()
+            .map(|p| Point {
+                x: p.x - bbox.min.x,
+                y: p.y - bbox.min.y,
+            })
+            .collect()
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox<T>> {
+        BoundingBox::from_points(&self.0)
+    }
+
+    /// A grid spanning the bounding box, and the point its top left cell
+    /// stands for. `None` if the cloud is empty.
+    pub fn into_bool_grid(self) -> Option<(Grid<bool>, Point<T>)> {
+        let bbox = self.bounding_box()?;
+        let index = |v: T| -> usize { v.try_into().ok().expect("point cloud too large") };
+        let width = index(bbox.width());
+        let mut inner = vec![false; width * index(bbox.height())];
+        for p in self.0 {
+            inner[index(p.y - bbox.min.y) * width + index(p.x - bbox.min.x)] = true;
         }
+        Some((Grid { inner, width }, bbox.min))
+    }
+
+    /// The points with at least one neighbor outside the cloud.
+    pub fn boundary(&self, neighborhood: Neighborhood) -> Self {
+        let expected = neighborhood.offsets().len();
+        self.0
+            .iter()
+            .filter(|p| {
+                let mut inside = 0;
+                p.neighbors_in(neighborhood).any(|n| {
+                    inside += 1;
+                    !self.contains(&n)
+                }) || inside < expected
+            })
+            .copied()
+            .collect()
+    }
+
+    /// The number of edges between a point of the cloud and one outside it.
+    pub fn perimeter(&self) -> usize {
+        self.0
+            .iter()
+            .map(|p| {
+                let inside = p
+                    .neighbors_in(Neighborhood::Orthogonal)
+                    .filter(|n| self.contains(n))
+                    .count();
+                4 - inside
+            })
+            .sum()
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Vector {
-    pub x: isize,
-    pub y: isize,
+impl<T: Coordinate> PartialEq for PointCloud<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
 }
 
-impl Vector {
+impl<T: Coordinate> Eq for PointCloud<T> {}
+
+impl<T> FromIterator<Point<T>> for PointCloud<T>
+where
+    T: Coordinate,
+{
+    fn from_iter<I: IntoIterator<Item = Point<T>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// The arithmetic operators panic on overflow in every build profile. The
+/// `checked_*` methods return `None` instead and the `wrapping_*` methods
+/// wrap around at the bounds of `T`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Vector<T = isize> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Vector<T>
+where
+    T: Signed,
+{
+    pub const ORTHOGONAL: [Self; 4] = [
+        Self {
+            x: T::ONE,
+            y: T::ZERO,
+        },
+        Self {
+            x: T::ZERO,
+            y: T::ONE,
+        },
+        Self {
+            x: T::NEG_ONE,
+            y: T::ZERO,
+        },
+        Self {
+            x: T::ZERO,
+            y: T::NEG_ONE,
+        },
+    ];
+
+    pub const ALL_DIRECTIONS: [Self; 8] = [
+        Self {
+            x: T::ONE,
+            y: T::ZERO,
+        },
+        Self {
+            x: T::ONE,
+            y: T::ONE,
+        },
+        Self {
+            x: T::ZERO,
+            y: T::ONE,
+        },
+        Self {
+            x: T::NEG_ONE,
+            y: T::ONE,
+        },
+        Self {
+            x: T::NEG_ONE,
+            y: T::ZERO,
+        },
+        Self {
+            x: T::NEG_ONE,
+            y: T::NEG_ONE,
+        },
+        Self {
+            x: T::ZERO,
+            y: T::NEG_ONE,
+        },
+        Self {
+            x: T::ONE,
+            y: T::NEG_ONE,
+        },
+    ];
+
     pub fn rev(&self) -> Self {
+        -*self
+    }
+
+    /// Rotates by 90 degrees clockwise, with y pointing down.
+    pub fn turn_right(&self) -> Self {
         Self {
-            x: -self.x,
-            y: -self.y,
+            x: -self.y,
+            y: self.x,
         }
     }
 
-    pub fn mul(&self, m: isize) -> Self {
+    pub fn turn_left(&self) -> Self {
         Self {
-            x: self.x * m,
-            y: self.y * m,
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    pub fn mul(&self, m: T) -> Self {
+        *self * m
+    }
+
+    pub fn manhattan(self) -> T::Distance {
+        self.x
+            .unsigned_abs()
+            .checked_add(self.y.unsigned_abs())
+            .expect("manhattan distance overflowed")
+    }
+
+    pub fn chebyshev(self) -> T::Distance {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+
+    /// The shortest vector with the same direction and integer components,
+    /// e.g. (4, -6) becomes (2, -3). The zero vector stays zero.
+    pub fn normalized(self) -> Self {
+        let divisor = coordinate::gcd(self.x, self.y);
+        if divisor == T::ZERO {
+            return self;
+        }
+        match divisor.checked_neg() {
+            Some(negated) => {
+                let divisor = divisor.max(negated);
+                Self {
+                    x: self.x / divisor,
+                    y: self.y / divisor,
+                }
+            }
+            // only when a component is T::MIN and the other 0 or T::MIN
+            None => self.signum(),
+        }
+    }
+
+    pub fn checked_add(self, v: Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add(v.x)?,
+            y: self.y.checked_add(v.y)?,
+        })
+    }
+
+    pub fn checked_sub(self, v: Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_sub(v.x)?,
+            y: self.y.checked_sub(v.y)?,
+        })
+    }
+
+    pub fn checked_mul(self, m: T) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_mul(m)?,
+            y: self.y.checked_mul(m)?,
+        })
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_neg()?,
+            y: self.y.checked_neg()?,
+        })
+    }
+
+    pub fn wrapping_add(self, v: Self) -> Self {
+        Self {
+            x: self.x.wrapping_add(v.x),
+            y: self.y.wrapping_add(v.y),
+        }
+    }
+
+    pub fn wrapping_sub(self, v: Self) -> Self {
+        Self {
+            x: self.x.wrapping_sub(v.x),
+            y: self.y.wrapping_sub(v.y),
+        }
+    }
+
+    pub fn wrapping_mul(self, m: T) -> Self {
+        Self {
+            x: self.x.wrapping_mul(m),
+            y: self.y.wrapping_mul(m),
+        }
+    }
+
+    pub fn wrapping_neg(self) -> Self {
+        Self {
+            x: self.x.wrapping_neg(),
+            y: self.y.wrapping_neg(),
         }
     }
 }
 
-impl Point {
-    pub fn add(&self, v: &Vector) -> Option<Self> {
+impl<T: Signed> std::ops::Add for Vector<T> {
+    type Output = Self;
+
+    fn add(self, v: Self) -> Self {
+        self.checked_add(v).expect("vector addition overflowed")
+    }
+}
+
+impl<T: Signed> std::ops::Sub for Vector<T> {
+    type Output = Self;
+
+    fn sub(self, v: Self) -> Self {
+        self.checked_sub(v).expect("vector subtraction overflowed")
+    }
+}
+
+impl<T: Signed> std::ops::Mul<T> for Vector<T> {
+    type Output = Self;
+
+    fn mul(self, m: T) -> Self {
+        self.checked_mul(m)
+            .expect("vector multiplication overflowed")
+    }
+}
+
+impl<T: Signed> std::ops::Neg for Vector<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("vector negation overflowed")
+    }
+}
+
+impl<T: Signed> std::ops::AddAssign for Vector<T> {
+    fn add_assign(&mut self, v: Self) {
+        *self = *self + v;
+    }
+}
+
+impl<T: Signed> std::ops::SubAssign for Vector<T> {
+    fn sub_assign(&mut self, v: Self) {
+        *self = *self - v;
+    }
+}
+
+impl<T> Point<T>
+where
+    T: Coordinate,
+{
+    /// `None` if the result does not fit in `T`, e.g. when it would be
+    /// negative for `usize`.
+    pub fn checked_add(self, v: Vector<T::Delta>) -> Option<Self> {
         Some(Self {
-            x: usize::try_from(isize::try_from(self.x).unwrap().checked_add(v.x).unwrap()).ok()?,
-            y: usize::try_from(isize::try_from(self.y).unwrap().checked_add(v.y).unwrap()).ok()?,
+            x: self.x.checked_add_delta(v.x)?,
+            y: self.y.checked_add_delta(v.y)?,
         })
     }
 
-    pub fn vector_to(&self, p: &Self) -> Vector {
-        Vector {
-            x: isize::try_from(p.x).unwrap() - isize::try_from(self.x).unwrap(),
-            y: isize::try_from(p.y).unwrap() - isize::try_from(self.y).unwrap(),
+    pub fn checked_sub(self, v: Vector<T::Delta>) -> Option<Self> {
+        self.checked_add(v.checked_neg()?)
+    }
+
+    /// `None` if a component of the difference does not fit in `T::Delta`.
+    pub fn checked_vector_to(self, p: Self) -> Option<Vector<T::Delta>> {
+        Some(Vector {
+            x: self.x.checked_delta_to(p.x)?,
+            y: self.y.checked_delta_to(p.y)?,
+        })
+    }
+
+    pub fn vector_to(&self, p: &Self) -> Vector<T::Delta> {
+        self.checked_vector_to(*p)
+            .expect("vector between points overflowed")
+    }
+
+    pub fn manhattan(self, p: Self) -> T::Distance {
+        self.x
+            .abs_diff(p.x)
+            .checked_add(self.y.abs_diff(p.y))
+            .expect("manhattan distance overflowed")
+    }
+
+    pub fn chebyshev(self, p: Self) -> T::Distance {
+        self.x.abs_diff(p.x).max(self.y.abs_diff(p.y))
+    }
+
+    pub fn neighbors_in(self, neighborhood: Neighborhood) -> impl Iterator<Item = Self> {
+        // every second direction is orthogonal
+        let step = match neighborhood {
+            Neighborhood::Orthogonal => 2,
+            Neighborhood::All => 1,
+        };
+        Vector::ALL_DIRECTIONS
+            .into_iter()
+            .step_by(step)
+            .filter_map(move |v| self.checked_add(v))
+    }
+}
+
+fn torus_step(p: usize, d: isize, size: usize, backwards: bool) -> usize {
+    let d = d
+        .rem_euclid(isize::try_from(size).expect("torus too large"))
+        .unsigned_abs();
+    let d = if backwards { size - d } else { d };
+    (p % size + d) % size
+}
+
+impl Point {
+    /// Moves on a `width` x `height` torus, so leaving one edge enters at the
+    /// opposite one. Unlike the `wrapping_*` methods, this wraps at the size
+    /// of the map rather than at the bounds of the type.
+    pub fn torus_add(self, v: Vector, width: usize, height: usize) -> Self {
+        Self {
+            x: torus_step(self.x, v.x, width, false),
+            y: torus_step(self.y, v.y, height, false),
+        }
+    }
+
+    /// Like [`Point::torus_add`], against the vector.
+    pub fn torus_sub(self, v: Vector, width: usize, height: usize) -> Self {
+        Self {
+            x: torus_step(self.x, v.x, width, true),
+            y: torus_step(self.y, v.y, height, true),
         }
     }
 
@@ -108,13 +510,121 @@ impl Point {
     pub fn all_neighbors(&self) -> impl Iterator<Item = Option<Self>> {
         let mut v = Vec::new();
         for (x, y) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
-            v.push(self.add(&Vector { x, y }));
+            v.push(self.checked_add(Vector { x, y }));
         }
         v.into_iter()
     }
 }
 
-#[derive(Debug, Clone)]
+impl<T: Coordinate> std::ops::Add<Vector<T::Delta>> for Point<T> {
+    type Output = Self;
+
+    fn add(self, v: Vector<T::Delta>) -> Self {
+        self.checked_add(v).expect("point out of range")
+    }
+}
+
+impl<T: Coordinate> std::ops::Sub<Vector<T::Delta>> for Point<T> {
+    type Output = Self;
+
+    fn sub(self, v: Vector<T::Delta>) -> Self {
+        self.checked_sub(v).expect("point out of range")
+    }
+}
+
+/// `b - a` is the vector from `a` to `b`.
+impl<T: Coordinate> std::ops::Sub for Point<T> {
+    type Output = Vector<T::Delta>;
+
+    fn sub(self, p: Self) -> Vector<T::Delta> {
+        p.vector_to(&self)
+    }
+}
+
+impl<T: Coordinate> std::ops::AddAssign<Vector<T::Delta>> for Point<T> {
+    fn add_assign(&mut self, v: Vector<T::Delta>) {
+        *self = *self + v;
+    }
+}
+
+impl<T: Coordinate> std::ops::SubAssign<Vector<T::Delta>> for Point<T> {
+    fn sub_assign(&mut self, v: Vector<T::Delta>) {
+        *self = *self - v;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The four cells sharing an edge, or six faces in 3D.
+    Orthogonal,
+    /// The eight cells sharing an edge or a corner, or 26 in 3D.
+    All,
+}
+
+impl Neighborhood {
+    pub fn offsets(self) -> &'static [Vector] {
+        match self {
+            Self::Orthogonal => &Vector::ORTHOGONAL,
+            Self::All => &Vector::ALL_DIRECTIONS,
+        }
+    }
+}
+
+/// Inclusive on both ends.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoundingBox<T = usize> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T> BoundingBox<T>
+where
+    T: Coordinate,
+{
+    pub fn from_point(point: &Point<T>) -> Self {
+        Self {
+            min: *point,
+            max: *point,
+        }
+    }
+
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let mut bbox = Self::from_point(points.next()?);
+        for point in points {
+            bbox.extend(point);
+        }
+        Some(bbox)
+    }
+
+    pub fn extend(&mut self, point: &Point<T>) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Grid<T> {
     inner: Vec<T>,
     width: usize,
@@ -152,24 +662,39 @@ where
 }
 
 #[derive(Debug, Clone)]
-pub struct GridCol<T> {
-    pub cells: Vec<T>,
+pub struct GridCol<'a, T> {
+    grid: &'a Grid<T>,
     pub x: usize,
 }
 
-impl<T> GridCol<T>
+impl<'a, T> GridCol<'a, T> {
+    pub fn cells(&self) -> impl Iterator<Item = &'a T> + use<'a, T> {
+        self.grid.inner[self.x..].iter().step_by(self.grid.width)
+    }
+}
+
+impl<T> GridCol<'_, T>
 where
     T: Copy,
 {
     pub fn points(&self) -> impl Iterator<Item = (Point, T)> + use<'_, T> {
-        self.cells
-            .iter()
+        self.cells()
             .enumerate()
             .map(|(y, cell)| (Point { y, x: self.x }, *cell))
     }
 }
 
 impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            inner: vec![value; width * height],
+            width,
+        }
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -192,13 +717,117 @@ impl<T> Grid<T> {
         self.inner.len() / self.width
     }
 
-    pub fn rows(&self) -> impl Iterator<Item = GridRow<T>> {
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotate_right(self) -> Self {
+        let (width, height) = (self.width(), self.height());
+        let mut cells: Vec<Option<T>> = self.inner.into_iter().map(Some).collect();
+        let mut inner = Vec::with_capacity(cells.len());
+        for x in 0..width {
+            for y in (0..height).rev() {
+                inner.push(cells[y * width + x].take().unwrap());
+            }
+        }
+        Self {
+            inner,
+            width: height,
+        }
+    }
+
+    /// Mirrors the grid along its vertical axis, so the first column becomes the last.
+    pub fn flip_horizontal(mut self) -> Self {
+        for row in self.inner.chunks_mut(self.width) {
+            row.reverse();
+        }
+        self
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = GridRow<'_, T>> {
         self.inner
             .chunks(self.width)
             .enumerate()
             .map(|(y, cells)| GridRow { cells, y })
     }
 
+    pub fn cols(&self) -> impl Iterator<Item = GridCol<'_, T>> {
+        (0..self.width()).map(|x| GridCol { grid: self, x })
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.inner[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.inner[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn col(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is out of bounds");
+        self.inner[x..].iter().step_by(self.width)
+    }
+
+    pub fn col_mut(&mut self, x: usize) -> impl Iterator<Item = &mut T> {
+        assert!(x < self.width, "column {x} is out of bounds");
+        self.inner[x..].iter_mut().step_by(self.width)
+    }
+
+    /// Panics if `cells` does not have exactly `width` elements.
+    pub fn set_row(&mut self, y: usize, cells: impl IntoIterator<Item = T>) {
+        let width = self.width;
+        let mut cells = cells.into_iter();
+        for cell in self.row_mut(y) {
+            *cell = cells.next().expect("too few cells for row");
+        }
+        assert!(cells.next().is_none(), "row is longer than {width}");
+    }
+
+    /// Panics if `cells` does not have exactly `height` elements.
+    pub fn set_col(&mut self, x: usize, cells: impl IntoIterator<Item = T>) {
+        let height = self.height();
+        let mut cells = cells.into_iter();
+        for cell in self.col_mut(x) {
+            *cell = cells.next().expect("too few cells for column");
+        }
+        assert!(cells.next().is_none(), "column is longer than {height}");
+    }
+
+    fn point_of(&self, index: usize) -> Point {
+        Point {
+            x: index % self.width,
+            y: index / self.width,
+        }
+    }
+
+    pub fn iter(&self) -> GridIter<'_, T> {
+        GridIter {
+            inner: self.inner.iter().enumerate(),
+            width: self.width,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> GridIterMut<'_, T> {
+        GridIterMut {
+            inner: self.inner.iter_mut().enumerate(),
+            width: self.width,
+        }
+    }
+
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.inner
+            .iter()
+            .position(predicate)
+            .map(|i| self.point_of(i))
+    }
+
+    pub fn count(&self, predicate: impl Fn(&T) -> bool) -> usize {
+        self.inner.iter().filter(|cell| predicate(cell)).count()
+    }
+
+    pub fn swap(&mut self, a: &Point, b: &Point) {
+        assert!(self.contains(a) && self.contains(b), "points out of bounds");
+        self.inner
+            .swap(a.y * self.width + a.x, b.y * self.width + b.x);
+    }
+
     pub fn contains(&self, point: &Point) -> bool {
         point.y < self.height() && point.x < self.width()
     }
@@ -259,15 +888,18 @@ pub struct GridPointOwned<T> {
 
 impl<T> Grid<T>
 where
-    T: Copy,
+    T: PartialEq,
 {
-    pub fn iter(&self) -> GridIter<T> {
-        GridIter {
-            point: Point { x: 0, y: 0 },
-            grid: self,
-        }
+    pub fn positions_of<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + use<'a, T> {
+        self.iter()
+            .filter_map(move |(point, cell)| (cell == value).then_some(point))
     }
+}
 
+impl<T> Grid<T>
+where
+    T: Copy,
+{
     pub fn neighbors_of_copy(&self, point: &Point) -> impl Iterator<Item = GridPointOwned<T>> {
         let mut v = Vec::new();
         for point in point.neighbors() {
@@ -280,19 +912,6 @@ where
         }
         v.into_iter()
     }
-
-    pub fn cols(&self) -> impl Iterator<Item = GridCol<T>> + use<'_, T> {
-        (0..self.width()).map(|x| GridCol {
-            x,
-            cells: self
-                .inner
-                .iter()
-                .skip(x)
-                .step_by(self.width())
-                .copied()
-                .collect(),
-        })
-    }
 }
 
 impl<T> Grid<T>
@@ -322,10 +941,7 @@ impl Grid<u8> {
     }
 }
 
-impl<T> std::ops::Index<&Point> for Grid<T>
-where
-    T: Copy,
-{
+impl<T> std::ops::Index<&Point> for Grid<T> {
     type Output = T;
 
     fn index(&self, index: &Point) -> &Self::Output {
@@ -333,44 +949,102 @@ where
     }
 }
 
+impl<T> std::ops::IndexMut<&Point> for Grid<T> {
+    fn index_mut(&mut self, index: &Point) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
 pub struct GridIter<'a, T> {
-    point: Point,
-    grid: &'a Grid<T>,
+    inner: std::iter::Enumerate<std::slice::Iter<'a, T>>,
+    width: usize,
 }
 
-impl<T> Iterator for GridIter<'_, T>
-where
-    T: Copy,
-{
-    type Item = (Point, T);
+impl<'a, T> Iterator for GridIter<'a, T> {
+    type Item = (Point, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        assert!(self.point.y <= self.grid.height());
-        let elem = self.grid.get(&self.point).map(|e| (self.point.clone(), *e));
+        let (i, cell) = self.inner.next()?;
+        Some((
+            Point {
+                x: i % self.width,
+                y: i / self.width,
+            },
+            cell,
+        ))
+    }
+}
 
-        if self.point.x == self.grid.width() - 1 {
-            self.point = Point {
-                x: 0,
-                y: self.point.y + 1,
-            }
-        } else {
-            self.point = Point {
-                x: self.point.x + 1,
-                y: self.point.y,
-            }
-        }
+pub struct GridIterMut<'a, T> {
+    inner: std::iter::Enumerate<std::slice::IterMut<'a, T>>,
+    width: usize,
+}
+
+impl<'a, T> Iterator for GridIterMut<'a, T> {
+    type Item = (Point, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, cell) = self.inner.next()?;
+        Some((
+            Point {
+                x: i % self.width,
+                y: i / self.width,
+            },
+            cell,
+        ))
+    }
+}
+
+pub struct GridIntoIter<T> {
+    inner: std::iter::Enumerate<std::vec::IntoIter<T>>,
+    width: usize,
+}
 
-        elem
+impl<T> Iterator for GridIntoIter<T> {
+    type Item = (Point, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, cell) = self.inner.next()?;
+        Some((
+            Point {
+                x: i % self.width,
+                y: i / self.width,
+            },
+            cell,
+        ))
     }
 }
 
 impl<T> IntoIterator for Grid<T> {
     type Item = (Point, T);
 
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+    type IntoIter = GridIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        GridIntoIter {
+            inner: self.inner.into_iter().enumerate(),
+            width: self.width,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = (Point, &'a T);
+
+    type IntoIter = GridIter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        todo!()
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Grid<T> {
+    type Item = (Point, &'a mut T);
+
+    type IntoIter = GridIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
@@ -452,8 +1126,9 @@ where
     })
 }
 
+/// Decimal digits of `num`, see [`digits::count`] for other bases.
 pub fn number_of_digits(num: usize) -> u32 {
-    num.ilog10() + 1
+    digits::count(u64::try_from(num).unwrap(), 10)
 }
 
 pub fn unique<T>(v: impl Iterator<Item = T>) -> Vec<T>
@@ -462,7 +1137,7 @@ where
 {
     let mut result = Vec::new();
     for elem in v {
-        if !result.iter().any(|e| *e == elem) {
+        if !result.contains(&elem) {
             result.push(elem.clone());
         }
     }
@@ -476,3 +1151,129 @@ pub fn whole_div(a: isize, b: isize) -> Option<isize> {
         Some(a / b)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn point_and_vector_arithmetic() {
+        let p = Point { x: 3, y: 5 };
+        let v = Vector { x: -4, y: 6 };
+        assert_eq!(p + Vector { x: -3, y: 1 }, Point { x: 0, y: 6 });
+        assert_eq!(p.checked_add(v), None);
+        assert_eq!(Point { x: 7, y: 1 } - p, Vector { x: 4, y: -4 });
+        assert_eq!(-v + v, Vector { x: 0, y: 0 });
+        assert_eq!(v.normalized(), Vector { x: -2, y: 3 });
+        let steep = Vector {
+            x: isize::MIN,
+            y: -1,
+        };
+        assert_eq!(steep.normalized(), steep);
+        assert_eq!(v.signum(), Vector { x: -1, y: 1 });
+        assert_eq!((v.manhattan(), v.chebyshev()), (10, 6));
+        assert_eq!(p.manhattan(Point { x: 0, y: 0 }), 8);
+        assert_eq!(p.torus_add(v * 3, 7, 4), Point { x: 5, y: 3 });
+        assert_eq!(p.torus_sub(v * 3, 7, 4), Point { x: 1, y: 3 });
+
+        let max = Vector {
+            x: isize::MAX,
+            y: 0,
+        };
+        assert_eq!(max.checked_add(max), None);
+        assert_eq!(max.wrapping_add(max), Vector { x: -2, y: 0 });
+        assert_eq!(
+            Point {
+                x: usize::MAX,
+                y: 0
+            }
+            .checked_vector_to(Point { x: 0, y: 0 }),
+            None
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "manhattan distance overflowed")]
+    fn manhattan_overflow() {
+        let far = Point {
+            x: usize::MAX,
+            y: usize::MAX,
+        };
+        let _ = far.manhattan(Point { x: 0, y: 0 });
+    }
+
+    #[test]
+    fn point_cloud() {
+        let cloud: PointCloud = (1..4)
+            .flat_map(|y| (2..5).map(move |x| Point { x, y }))
+            .collect();
+        assert_eq!(cloud.perimeter(), 12);
+        assert_eq!(cloud.boundary(Neighborhood::Orthogonal).area(), 8);
+        assert_eq!(
+            cloud.sorted()[..2],
+            [Point { x: 2, y: 1 }, Point { x: 3, y: 1 }]
+        );
+
+        let normalized = cloud.normalized();
+        assert_eq!(normalized.bounding_box().unwrap().min, Point { x: 0, y: 0 });
+        assert_eq!(
+            normalized.translate(Vector { x: 2, y: 1 }),
+            Some(cloud.clone())
+        );
+        assert_eq!(normalized.translate(Vector { x: -1, y: 0 }), None);
+
+        let corner = PointCloud::from(vec![Point { x: 4, y: 3 }, Point { x: 5, y: 3 }]);
+        assert_eq!(cloud.intersection(&corner).area(), 1);
+        assert_eq!(cloud.union(&corner).area(), 10);
+        assert_eq!(corner.difference(&cloud).sorted(), [Point { x: 5, y: 3 }]);
+
+        let (grid, offset) = corner.into_bool_grid().unwrap();
+        assert_eq!(
+            (grid.width(), grid.height(), offset),
+            (2, 1, Point { x: 4, y: 3 })
+        );
+        assert_eq!(PointCloud::<usize>::new().into_bool_grid(), None);
+    }
+
+    #[test]
+    fn signed_points() {
+        let p: Point<i64> = Point { x: -3, y: 2 };
+        assert_eq!(p.neighbors_in(Neighborhood::All).count(), 8);
+        assert_eq!(p + Vector { x: -1, y: -4 }, Point { x: -4, y: -2 });
+        let bbox = BoundingBox::from_points(&[p, Point { x: 5, y: -1 }]).unwrap();
+        assert_eq!((bbox.width(), bbox.height(), bbox.area()), (9, 4, 36));
+        assert_eq!(
+            Point::<u8> { x: 0, y: 0 }
+                .neighbors_in(Neighborhood::All)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn grid_iteration_and_mutation() {
+        let mut grid: Grid<char> = Grid::from_str("ab\ncd\n");
+        grid[&Point { x: 1, y: 1 }] = 'x';
+        grid.swap(&Point { x: 0, y: 0 }, &Point { x: 1, y: 0 });
+        grid.set_col(0, ['1', '2']);
+        for (point, cell) in &mut grid {
+            if point.y == 0 {
+                *cell = cell.to_ascii_uppercase();
+            }
+        }
+        assert_eq!(grid.row(0), &['1', 'A']);
+        assert_eq!(grid.col(1).collect::<String>(), "Ax");
+        assert_eq!(grid.find(|c| *c == 'x'), Some(Point { x: 1, y: 1 }));
+        assert_eq!(grid.positions_of(&'2').count(), 1);
+        assert_eq!(grid.count(|c| c.is_ascii_digit()), 2);
+        assert_eq!(
+            grid.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Point { x: 0, y: 0 }, '1'),
+                (Point { x: 1, y: 0 }, 'A'),
+                (Point { x: 0, y: 1 }, '2'),
+                (Point { x: 1, y: 1 }, 'x'),
+            ]
+        );
+    }
+}
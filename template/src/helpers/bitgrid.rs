@@ -0,0 +1,374 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use super::{Grid, Neighborhood, Point, Vector};
+
+/// A grid of booleans with 64 cells packed into every word. Every row starts
+/// at a new word, so shifting and combining whole grids works a word at a
+/// time. Padding bits behind the last column are always zero.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    row_words: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let row_words = width.div_ceil(64);
+        Self {
+            words: vec![0; row_words * height],
+            width,
+            height,
+            row_words,
+        }
+    }
+
+    pub fn from_points<'a>(
+        width: usize,
+        height: usize,
+        points: impl IntoIterator<Item = &'a Point>,
+    ) -> Self {
+        let mut grid = Self::new(width, height);
+        for point in points {
+            grid.set(point, true);
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    fn position(&self, point: &Point) -> (usize, u64) {
+        (point.y * self.row_words + point.x / 64, 1 << (point.x % 64))
+    }
+
+    /// `false` outside of the grid.
+    pub fn get(&self, point: &Point) -> bool {
+        if !self.contains(point) {
+            return false;
+        }
+        let (word, bit) = self.position(point);
+        self.words[word] & bit != 0
+    }
+
+    pub fn set(&mut self, point: &Point, value: bool) {
+        assert!(self.contains(point), "{point:?} is outside of the grid");
+        let (word, bit) = self.position(point);
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    /// Returns whether the cell was not set before, like `HashSet::insert`.
+    pub fn insert(&mut self, point: &Point) -> bool {
+        let new = !self.get(point);
+        self.set(point, true);
+        new
+    }
+
+    pub fn remove(&mut self, point: &Point) -> bool {
+        let present = self.get(point);
+        if present {
+            self.set(point, false);
+        }
+        present
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn iter_ones(&self) -> impl Iterator<Item = Point> + use<'_> {
+        self.words.iter().enumerate().flat_map(move |(i, word)| {
+            let (y, x0) = (i / self.row_words, (i % self.row_words) * 64);
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(Point { x: x0 + bit, y })
+            })
+        })
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    fn last_word_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    fn clear_padding(&mut self) {
+        let mask = self.last_word_mask();
+        for y in 0..self.height {
+            if let Some(last) = self.row_mut(y).last_mut() {
+                *last &= mask;
+            }
+        }
+    }
+
+    /// Moves every cell by the vector. Cells moved outside of the grid are lost,
+    /// the cells that get uncovered are `false`.
+    pub fn shifted(&self, v: &Vector) -> Self {
+        let mut result = Self::new(self.width, self.height);
+        let dy = v.y.unsigned_abs();
+        if dy >= self.height || v.x.unsigned_abs() >= self.width {
+            return result;
+        }
+        for y in 0..(self.height - dy) {
+            let (from, to) = if v.y >= 0 { (y, y + dy) } else { (y + dy, y) };
+            let target = &mut result.words[to * self.row_words..(to + 1) * self.row_words];
+            shift_row(self.row(from), target, v.x);
+        }
+        result.clear_padding();
+        result
+    }
+
+    /// For every cell, the number of set cells in its neighborhood. The counting
+    /// is done with bit-sliced adders, so it stays word-parallel.
+    pub fn neighbor_counts(&self, neighborhood: Neighborhood) -> NeighborCounts {
+        let mut counts = NeighborCounts {
+            planes: std::array::from_fn(|_| Self::new(self.width, self.height)),
+        };
+        for offset in neighborhood.offsets() {
+            counts.add(&self.shifted(offset));
+        }
+        counts
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        Grid {
+            inner: (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| Point { x, y }))
+                .map(|point| self.get(&point))
+                .collect(),
+            width: self.width,
+        }
+    }
+}
+
+// Shifts the bits of a row towards higher x (positive `by`) or lower x.
+fn shift_row(source: &[u64], target: &mut [u64], by: isize) {
+    let words = by.unsigned_abs() / 64;
+    let bits = by.unsigned_abs() % 64;
+    let len = source.len();
+    for (i, word) in target.iter_mut().enumerate() {
+        let at = |j: Option<usize>| j.and_then(|j| source.get(j)).copied().unwrap_or(0);
+        *word = if by >= 0 {
+            let main = at(i.checked_sub(words));
+            let carry = at(i.checked_sub(words + 1));
+            if bits == 0 {
+                main
+            } else {
+                (main << bits) | (carry >> (64 - bits))
+            }
+        } else {
+            let main = at(Some(i + words).filter(|j| *j < len));
+            let carry = at(Some(i + words + 1).filter(|j| *j < len));
+            if bits == 0 {
+                main
+            } else {
+                (main >> bits) | (carry << (64 - bits))
+            }
+        };
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut result = Self::new(grid.width(), grid.height());
+        for (i, cell) in grid.inner.iter().enumerate() {
+            if *cell {
+                result.set(
+                    &Point {
+                        x: i % grid.width,
+                        y: i / grid.width,
+                    },
+                    true,
+                );
+            }
+        }
+        result
+    }
+}
+
+macro_rules! bit_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $assign_trait<&BitGrid> for BitGrid {
+            fn $assign_method(&mut self, rhs: &BitGrid) {
+                assert_eq!((self.width, self.height), (rhs.width, rhs.height), "grid sizes differ");
+                for (a, b) in self.words.iter_mut().zip(&rhs.words) {
+                    *a = *a $op *b;
+                }
+            }
+        }
+
+        impl $trait<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, rhs: &BitGrid) -> BitGrid {
+                let mut result = self.clone();
+                result.$assign_method(rhs);
+                result
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        for word in &mut result.words {
+            *word = !*word;
+        }
+        result.clear_padding();
+        result
+    }
+}
+
+/// Per-cell counts of up to 15, stored as four bit planes (the binary digits
+/// of the count).
+#[derive(Debug, Clone)]
+pub struct NeighborCounts {
+    planes: [BitGrid; 4],
+}
+
+impl NeighborCounts {
+    fn add(&mut self, grid: &BitGrid) {
+        let mut carry = grid.words.clone();
+        for plane in &mut self.planes {
+            for (word, carry) in plane.words.iter_mut().zip(carry.iter_mut()) {
+                let sum = *word ^ *carry;
+                *carry &= *word;
+                *word = sum;
+            }
+        }
+    }
+
+    pub fn get(&self, point: &Point) -> u8 {
+        self.planes
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| u8::from(plane.get(point)) << i)
+            .sum()
+    }
+
+    /// All cells where the count is exactly `n`. The planes hold counts up
+    /// to 15, so nothing matches anything larger.
+    pub fn equal_to(&self, n: u8) -> BitGrid {
+        let empty = BitGrid::new(self.planes[0].width, self.planes[0].height);
+        if usize::from(n) >= 1 << self.planes.len() {
+            return empty;
+        }
+        let mut result = !&empty;
+        for (i, plane) in self.planes.iter().enumerate() {
+            if n & (1 << i) == 0 {
+                result &= &!plane;
+            } else {
+                result &= plane;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_and_count() {
+        let mut grid = BitGrid::new(130, 3);
+        assert!(grid.insert(&Point { x: 0, y: 0 }));
+        assert!(!grid.insert(&Point { x: 0, y: 0 }));
+        grid.set(&Point { x: 64, y: 1 }, true);
+        grid.set(&Point { x: 129, y: 2 }, true);
+        assert_eq!(grid.count_ones(), 3);
+        assert_eq!(
+            grid.iter_ones().collect::<Vec<_>>(),
+            vec![
+                Point { x: 0, y: 0 },
+                Point { x: 64, y: 1 },
+                Point { x: 129, y: 2 }
+            ]
+        );
+        assert_eq!((!&grid).count_ones(), 130 * 3 - 3);
+    }
+
+    #[test]
+    fn shifts() {
+        let mut grid = BitGrid::new(100, 2);
+        grid.set(&Point { x: 63, y: 0 }, true);
+        grid.set(&Point { x: 99, y: 0 }, true);
+        let right = grid.shifted(&Vector { x: 1, y: 1 });
+        assert_eq!(
+            right.iter_ones().collect::<Vec<_>>(),
+            vec![Point { x: 64, y: 1 }]
+        );
+        let left = grid.shifted(&Vector { x: -70, y: 0 });
+        assert_eq!(
+            left.iter_ones().collect::<Vec<_>>(),
+            vec![Point { x: 29, y: 0 }]
+        );
+    }
+
+    #[test]
+    fn ops_and_counts() {
+        let a = BitGrid::from(&Grid::from_str_with("##.\n.#.\n...\n", |c| c == '#'));
+        let b = BitGrid::from(&Grid::from_str_with("#..\n.#.\n..#\n", |c| c == '#'));
+        assert_eq!((&a & &b).count_ones(), 2);
+        assert_eq!((&a | &b).count_ones(), 4);
+        assert_eq!((&a ^ &b).count_ones(), 2);
+
+        let counts = a.neighbor_counts(Neighborhood::All);
+        assert_eq!(counts.get(&Point { x: 0, y: 1 }), 3);
+        assert_eq!(counts.get(&Point { x: 1, y: 1 }), 2);
+        assert_eq!(counts.get(&Point { x: 2, y: 2 }), 1);
+        assert_eq!(
+            counts.equal_to(3).iter_ones().collect::<Vec<_>>(),
+            vec![Point { x: 0, y: 1 }]
+        );
+        assert_eq!(
+            counts.equal_to(3).to_grid().get(&Point { x: 0, y: 1 }),
+            Some(&true)
+        );
+        assert_eq!(counts.equal_to(16).count_ones(), 0);
+        assert_eq!(counts.equal_to(255).count_ones(), 0);
+    }
+}
@@ -0,0 +1,126 @@
+/// The number of digits of `n` in `base`. Zero has one digit.
+pub fn count(n: u64, base: u64) -> u32 {
+    assert!(base >= 2, "base has to be at least 2");
+    n.checked_ilog(base).map_or(1, |log| log + 1)
+}
+
+/// Splits off the lowest `digits` digits, so `split_at(1234, 10, 1)` is
+/// `(123, 4)`. If `n` has no more digits than that, the high part is 0.
+pub fn split_at(n: u64, base: u64, digits: u32) -> (u64, u64) {
+    match base.checked_pow(digits) {
+        Some(power) => (n / power, n % power),
+        None => (0, n),
+    }
+}
+
+/// Splits a number with an even digit count into its two halves, like
+/// stones engraved with 1000 turning into 10 and 0.
+pub fn split_half(n: u64, base: u64) -> Option<(u64, u64)> {
+    let digits = count(n, base);
+    digits
+        .is_multiple_of(2)
+        .then(|| split_at(n, base, digits / 2))
+}
+
+/// Writes `low` after `high`, so `concat(12, 345, 10)` is `Some(12345)`.
+/// `None` on overflow.
+pub fn concat(high: u64, low: u64, base: u64) -> Option<u64> {
+    base.checked_pow(count(low, base))?
+        .checked_mul(high)?
+        .checked_add(low)
+}
+
+/// The number with the digits in reverse order, dropping leading zeros of
+/// the result. `None` on overflow.
+pub fn reverse(n: u64, base: u64) -> Option<u64> {
+    from_digits(digits(n, base).rev(), base)
+}
+
+/// `None` on overflow.
+pub fn from_digits(digits: impl IntoIterator<Item = u64>, base: u64) -> Option<u64> {
+    digits.into_iter().try_fold(0_u64, |number, digit| {
+        assert!(digit < base, "digit {digit} out of range for base {base}");
+        number.checked_mul(base)?.checked_add(digit)
+    })
+}
+
+/// The digits of `n` from the most significant one. Reversing it yields
+/// them from the least significant one, without collecting first.
+pub fn digits(n: u64, base: u64) -> Digits {
+    Digits {
+        n,
+        base,
+        len: count(n, base),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Digits {
+    n: u64,
+    base: u64,
+    len: u32,
+}
+
+impl Iterator for Digits {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.len = self.len.checked_sub(1)?;
+        let (high, low) = split_at(self.n, self.base, self.len);
+        self.n = low;
+        Some(high)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.len).unwrap();
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Digits {
+    fn next_back(&mut self) -> Option<u64> {
+        self.len = self.len.checked_sub(1)?;
+        let digit = self.n % self.base;
+        self.n /= self.base;
+        Some(digit)
+    }
+}
+
+impl ExactSizeIterator for Digits {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counting_and_splitting() {
+        assert_eq!(count(0, 10), 1);
+        assert_eq!(count(9, 10), 1);
+        assert_eq!(count(10, 10), 2);
+        assert_eq!(count(u64::MAX, 10), 20);
+        assert_eq!(count(255, 16), 2);
+        assert_eq!(split_half(1000, 10), Some((10, 0)));
+        assert_eq!(split_half(0, 10), None);
+        assert_eq!(split_half(0b1011, 2), Some((0b10, 0b11)));
+        assert_eq!(split_at(1234, 10, 25), (0, 1234));
+        // concatenation operator of 2024 day 7
+        assert_eq!(concat(15, 6, 10), Some(156));
+        assert_eq!(concat(15, 0, 10), Some(150));
+        assert_eq!(concat(u64::MAX, 1, 10), None);
+    }
+
+    #[test]
+    fn iterating() {
+        assert_eq!(digits(1203, 10).collect::<Vec<_>>(), [1, 2, 0, 3]);
+        assert_eq!(digits(1203, 10).rev().collect::<Vec<_>>(), [3, 0, 2, 1]);
+        let mut both = digits(12_345, 10);
+        assert_eq!(both.len(), 5);
+        assert_eq!((both.next(), both.next_back()), (Some(1), Some(5)));
+        assert_eq!(both.collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(digits(0, 10).collect::<Vec<_>>(), [0]);
+        assert_eq!(digits(0xbeef, 16).collect::<Vec<_>>(), [11, 14, 14, 15]);
+        assert_eq!(reverse(1200, 10), Some(21));
+        assert_eq!(reverse(u64::MAX, 10), None);
+        assert_eq!(from_digits([4, 2], 10), Some(42));
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Axial coordinates on a grid of flat-topped hexagons. `q` grows to the
+/// south east and `r` to the south, so `s = -q - r` is the third cube
+/// coordinate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl HexDirection {
+    /// Clockwise, starting at north.
+    pub const ALL: [Self; 6] = [Self::N, Self::NE, Self::SE, Self::S, Self::SW, Self::NW];
+
+    pub fn offset(self) -> Hex {
+        let (q, r) = match self {
+            Self::N => (0, -1),
+            Self::NE => (1, -1),
+            Self::SE => (1, 0),
+            Self::S => (0, 1),
+            Self::SW => (-1, 1),
+            Self::NW => (-1, 0),
+        };
+        Hex { q, r }
+    }
+
+    /// Parses comma separated steps like `ne,ne,s`.
+    pub fn parse_steps(input: &str) -> Result<Vec<Self>, ParseHexDirectionError> {
+        input
+            .trim()
+            .split(',')
+            .map(|step| step.trim().parse())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHexDirectionError(pub String);
+
+impl Display for ParseHexDirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid hex direction {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseHexDirectionError {}
+
+impl FromStr for HexDirection {
+    type Err = ParseHexDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "n" => Ok(Self::N),
+            "ne" => Ok(Self::NE),
+            "se" => Ok(Self::SE),
+            "s" => Ok(Self::S),
+            "sw" => Ok(Self::SW),
+            "nw" => Ok(Self::NW),
+            _ => Err(ParseHexDirectionError(s.to_owned())),
+        }
+    }
+}
+
+impl Hex {
+    pub const ORIGIN: Self = Self { q: 0, r: 0 };
+
+    pub fn s(self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn step(self, direction: HexDirection) -> Self {
+        self + direction.offset()
+    }
+
+    pub fn walk(self, steps: impl IntoIterator<Item = HexDirection>) -> Self {
+        steps.into_iter().fold(self, Self::step)
+    }
+
+    /// In [`HexDirection::ALL`] order.
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        HexDirection::ALL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// The number of steps between the two hexes.
+    pub fn distance(self, other: Self) -> usize {
+        let d = other - self;
+        (d.q.unsigned_abs() + d.r.unsigned_abs() + d.s().unsigned_abs()) / 2
+    }
+
+    /// Rotates by `times` 60 degree turns clockwise around `center`. Negative
+    /// values turn counterclockwise.
+    pub fn rotate_around(self, center: Self, times: isize) -> Self {
+        let mut d = self - center;
+        for _ in 0..times.rem_euclid(6) {
+            d = Self { q: -d.r, r: -d.s() };
+        }
+        center + d
+    }
+}
+
+impl std::ops::Add for Hex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            q: self.q + other.q,
+            r: self.r + other.r,
+        }
+    }
+}
+
+impl std::ops::Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            q: self.q - other.q,
+            r: self.r - other.r,
+        }
+    }
+}
+
+/// A sparse map of hexes where missing cells hold `T::default()`, so it can
+/// grow in every direction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HexMap<T> {
+    cells: HashMap<Hex, T>,
+}
+
+impl<T> HexMap<T>
+where
+    T: Default + PartialEq,
+{
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, hex: &Hex) -> Option<&T> {
+        self.cells.get(hex)
+    }
+
+    /// Storing the default value removes the cell.
+    pub fn insert(&mut self, hex: Hex, value: T) {
+        if value == T::default() {
+            self.cells.remove(&hex);
+        } else {
+            self.cells.insert(hex, value);
+        }
+    }
+
+    /// The number of cells that do not hold the default value.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Hex, &T)> {
+        self.cells.iter()
+    }
+
+    /// One generation of a cellular automaton. The rule gets the current
+    /// value and the neighbors in [`HexDirection::ALL`] order, for every cell
+    /// that is set or next to one that is.
+    pub fn step(&self, rule: impl Fn(&T, [&T; 6]) -> T) -> Self {
+        let empty = T::default();
+        let value = |hex: &Hex| self.cells.get(hex).unwrap_or(&empty);
+        let mut next = Self::new();
+        let mut candidates: Vec<Hex> = self.cells.keys().flat_map(|h| h.neighbors()).collect();
+        candidates.extend(self.cells.keys());
+        candidates.sort_unstable();
+        candidates.dedup();
+        for hex in candidates {
+            let neighbors = HexDirection::ALL.map(|d| value(&hex.step(d)));
+            next.insert(hex, rule(value(&hex), neighbors));
+        }
+        next
+    }
+}
+
+impl HexMap<bool> {
+    /// Flips the cell and returns its new state.
+    pub fn toggle(&mut self, hex: Hex) -> bool {
+        let set = !self.get(&hex).copied().unwrap_or_default();
+        self.insert(hex, set);
+        set
+    }
+}
+
+impl<T> FromIterator<(Hex, T)> for HexMap<T>
+where
+    T: Default + PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = (Hex, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (hex, value) in iter {
+            map.insert(hex, value);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        for (steps, distance) in [
+            ("ne,ne,ne", 3),
+            ("ne,ne,sw,sw", 0),
+            ("ne,ne,s,s", 2),
+            ("se,sw,se,sw,sw", 3),
+        ] {
+            let end = Hex::ORIGIN.walk(HexDirection::parse_steps(steps).unwrap());
+            assert_eq!(Hex::ORIGIN.distance(end), distance);
+        }
+        assert_eq!(
+            HexDirection::parse_steps("n,e"),
+            Err(ParseHexDirectionError("e".to_owned()))
+        );
+    }
+
+    #[test]
+    fn rotation() {
+        let center = Hex { q: 2, r: -1 };
+        let ne = center.step(HexDirection::NE);
+        assert_eq!(ne.rotate_around(center, 1), center.step(HexDirection::SE));
+        assert_eq!(ne.rotate_around(center, -1), center.step(HexDirection::N));
+        assert_eq!(ne.rotate_around(center, 6), ne);
+    }
+
+    #[test]
+    fn automaton() {
+        let mut tiles = HexMap::new();
+        tiles.toggle(Hex::ORIGIN);
+        tiles.toggle(Hex::ORIGIN.step(HexDirection::N));
+        let rule = |black: &bool, neighbors: [&bool; 6]| {
+            let count = neighbors.iter().filter(|n| ***n).count();
+            if *black {
+                (1..=2).contains(&count)
+            } else {
+                count == 2
+            }
+        };
+        let next = tiles.step(rule);
+        assert_eq!(next.len(), 4);
+        assert_eq!(next.get(&Hex::ORIGIN.step(HexDirection::NW)), Some(&true));
+        assert!(tiles.toggle(Hex { q: 5, r: 5 }));
+        assert!(!tiles.toggle(Hex { q: 5, r: 5 }));
+        assert_eq!(tiles.len(), 2);
+    }
+}
@@ -0,0 +1,259 @@
+use std::io::{self, Write};
+
+use super::{BoundingBox, Grid, Point, PointCloud};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+
+    fn luma(self) -> u8 {
+        let luma =
+            (299 * u32::from(self.0) + 587 * u32::from(self.1) + 114 * u32::from(self.2)) / 1000;
+        u8::try_from(luma).unwrap()
+    }
+}
+
+/// An RGB image that can be written as PNG, PPM or PGM without any external
+/// dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Every cell becomes a `scale` x `scale` square.
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, color: impl Fn(&T) -> Rgb) -> Self {
+        assert!(scale > 0, "scale must be positive");
+        let (width, height) = (grid.width() * scale, grid.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in grid.rows() {
+            let colors: Vec<Rgb> = row.cells.iter().map(&color).collect();
+            for _ in 0..scale {
+                for c in &colors {
+                    pixels.extend(std::iter::repeat_n(*c, scale));
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// The image spans from the origin to the furthest point, so coordinates
+    /// stay the same as in the puzzle.
+    pub fn from_points(
+        points: &PointCloud,
+        scale: usize,
+        foreground: Rgb,
+        background: Rgb,
+    ) -> Self {
+        let mut bbox = BoundingBox::from_point(&Point { x: 0, y: 0 });
+        for point in points.iter() {
+            bbox.extend(point);
+        }
+        let mut grid = Grid::new(bbox.width(), bbox.height(), false);
+        for point in points.iter() {
+            grid[point] = true;
+        }
+        Self::from_grid(
+            &grid,
+            scale,
+            |set| if *set { foreground } else { background },
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn write_ppm(&self, mut w: impl Write) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let data: Vec<u8> = self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]).collect();
+        w.write_all(&data)
+    }
+
+    /// Grayscale, converted with the usual luma weights.
+    pub fn write_pgm(&self, mut w: impl Write) -> io::Result<()> {
+        write!(w, "P5\n{} {}\n255\n", self.width, self.height)?;
+        let data: Vec<u8> = self.pixels.iter().map(|p| p.luma()).collect();
+        w.write_all(&data)
+    }
+
+    /// The image data is stored uncompressed inside the zlib stream, which
+    /// keeps the encoder tiny. Files get big, but every viewer opens them.
+    pub fn write_png(&self, mut w: impl Write) -> io::Result<()> {
+        let too_large = |_| io::Error::new(io::ErrorKind::InvalidInput, "image too large for PNG");
+        let width = u32::try_from(self.width).map_err(too_large)?;
+        let height = u32::try_from(self.height).map_err(too_large)?;
+
+        w.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::new();
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        // 8 bit RGB, default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+        write_chunk(&mut w, *b"IHDR", &header)?;
+
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            // filter type "none"
+            raw.push(0);
+            raw.extend(row.iter().flat_map(|p| [p.0, p.1, p.2]));
+        }
+        write_chunk(&mut w, *b"IDAT", &zlib_stored(&raw))?;
+        write_chunk(&mut w, *b"IEND", &[])
+    }
+
+    /// Picks the format from the file extension (`png`, `ppm` or `pgm`).
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        // only touch the file once the format is known
+        let create = || std::fs::File::create(path).map(io::BufWriter::new);
+        match extension.as_deref() {
+            Some("png") => self.write_png(create()?),
+            Some("ppm") => self.write_ppm(create()?),
+            Some("pgm") => self.write_pgm(create()?),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown image format for {}", path.display()),
+            )),
+        }
+    }
+}
+
+fn write_chunk(w: &mut impl Write, kind: [u8; 4], data: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "chunk too large"))?;
+    w.write_all(&len.to_be_bytes())?;
+    w.write_all(&kind)?;
+    w.write_all(data)?;
+    let mut crc = Crc32::new();
+    crc.update(&kind);
+    crc.update(data);
+    w.write_all(&crc.finish().to_be_bytes())
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    // deflate, 32K window, no preset dictionary, fastest
+    let mut out = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = if data.is_empty() {
+        vec![&[]]
+    } else {
+        data.chunks(MAX_BLOCK).collect()
+    };
+    for (i, block) in blocks.iter().enumerate() {
+        let last = i == blocks.len() - 1;
+        out.push(u8::from(last));
+        let len = u16::try_from(block.len()).unwrap();
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(*block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + u32::from(*byte)) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
+
+struct Crc32(u32);
+
+impl Crc32 {
+    fn new() -> Self {
+        Self(0xffff_ffff)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.0 ^= u32::from(*byte);
+            for _ in 0..8 {
+                let mask = (self.0 & 1).wrapping_neg();
+                self.0 = (self.0 >> 1) ^ (0xedb8_8320 & mask);
+            }
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn scaled_grid() {
+        let grid: Grid<char> = Grid::from_str("#.\n");
+        let image = Image::from_grid(
+            &grid,
+            2,
+            |c| if *c == '#' { Rgb::WHITE } else { Rgb::BLACK },
+        );
+        assert_eq!((image.width(), image.height()), (4, 2));
+
+        let mut pgm = Vec::new();
+        image.write_pgm(&mut pgm).unwrap();
+        assert_eq!(pgm, b"P5\n4 2\n255\n\xff\xff\x00\x00\xff\xff\x00\x00");
+
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 4, 0, 0, 0, 2]);
+        // IEND is always the same, checksum included
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+    }
+
+    #[test]
+    fn points() {
+        let cloud = PointCloud::from(vec![Point { x: 2, y: 1 }]);
+        let image = Image::from_points(&cloud, 1, Rgb::WHITE, Rgb::BLACK);
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert_eq!(&ppm[..11], b"P6\n3 2\n255\n");
+        assert_eq!(&ppm[11 + 15..], &[255, 255, 255]);
+    }
+
+    #[test]
+    fn unknown_format() {
+        let path = std::env::temp_dir().join(format!("image-{}.jpg", std::process::id()));
+        let image = Image::from_grid(&Grid::new(1, 1, ()), 1, |()| Rgb::BLACK);
+        let error = image.save(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,148 @@
+use super::rational::Rational;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solutions {
+    Unique(Vec<Rational>),
+    /// The equations contradict each other.
+    Inconsistent,
+    /// Some unknowns are free, e.g. two buttons moving the claw the same way.
+    Infinite,
+}
+
+impl Solutions {
+    /// The unique solution, if every value in it is a whole number.
+    pub fn integers(&self) -> Option<Vec<i128>> {
+        match self {
+            Self::Unique(values) => values.iter().map(|value| value.to_integer()).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Solves `coefficients * x = constants` by Gauss-Jordan elimination. Every
+/// row of `coefficients` is one equation and needs a value per unknown.
+pub fn solve(coefficients: &[Vec<Rational>], constants: &[Rational]) -> Solutions {
+    assert_eq!(
+        coefficients.len(),
+        constants.len(),
+        "need one constant per equation"
+    );
+    let unknowns = coefficients.first().map_or(0, Vec::len);
+    let mut rows: Vec<Vec<Rational>> = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, constant)| {
+            assert_eq!(row.len(), unknowns, "rows differ in length");
+            row.iter().copied().chain([*constant]).collect()
+        })
+        .collect();
+
+    let mut pivots = Vec::new();
+    for column in 0..unknowns {
+        let row = pivots.len();
+        let Some(pivot) = (row..rows.len()).find(|r| !rows[*r][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(row, pivot);
+        let factor = rows[row][column].recip();
+        for value in &mut rows[row] {
+            *value *= factor;
+        }
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            let factor = values[column];
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot_value) in values.iter_mut().zip(&pivot_row).skip(column) {
+                *value -= factor * *pivot_value;
+            }
+        }
+        pivots.push(column);
+    }
+
+    // left over rows are all zero on the left, so their constant has to be too
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[unknowns].is_zero())
+    {
+        Solutions::Inconsistent
+    } else if pivots.len() < unknowns {
+        Solutions::Infinite
+    } else {
+        Solutions::Unique(
+            rows.iter()
+                .take(unknowns)
+                .map(|row| row[unknowns])
+                .collect(),
+        )
+    }
+}
+
+/// Like [`solve`] for integer equations, only returning whole solutions.
+pub fn solve_integers(coefficients: &[Vec<i64>], constants: &[i64]) -> Option<Vec<i128>> {
+    let coefficients: Vec<Vec<Rational>> = coefficients
+        .iter()
+        .map(|row| row.iter().map(|c| Rational::from(*c)).collect())
+        .collect();
+    let constants: Vec<Rational> = constants.iter().map(|c| Rational::from(*c)).collect();
+    solve(&coefficients, &constants).integers()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn claw_machines() {
+        // button presses for 2024 day 13, one column per button
+        assert_eq!(
+            solve_integers(&[vec![94, 22], vec![34, 67]], &[8400, 5400]),
+            Some(vec![80, 40])
+        );
+        assert_eq!(
+            solve_integers(&[vec![26, 67], vec![66, 21]], &[12_748, 12_176]),
+            None
+        );
+        let big = 10_000_000_000_000;
+        assert_eq!(
+            solve_integers(&[vec![26, 67], vec![66, 21]], &[12_748 + big, 12_176 + big]),
+            Some(vec![118_679_050_709, 103_199_174_542])
+        );
+
+        let same_direction = [vec![1_i64, 2], vec![1, 2]]
+            .map(|row| row.into_iter().map(Rational::from).collect::<Vec<_>>());
+        assert_eq!(
+            solve(
+                &same_direction,
+                &[Rational::from(3_i64), Rational::from(3_i64)]
+            ),
+            Solutions::Infinite
+        );
+        assert_eq!(
+            solve(
+                &same_direction,
+                &[Rational::from(3_i64), Rational::from(4_i64)]
+            ),
+            Solutions::Inconsistent
+        );
+    }
+
+    #[test]
+    fn three_unknowns() {
+        let coefficients = [vec![2_i64, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]]
+            .map(|row| row.into_iter().map(Rational::from).collect::<Vec<_>>());
+        let constants = [8_i64, -11, -3].map(Rational::from);
+        assert_eq!(
+            solve(&coefficients, &constants),
+            Solutions::Unique(vec![
+                Rational::from(2_i64),
+                Rational::from(3_i64),
+                Rational::from(-1_i64)
+            ])
+        );
+        let halves = solve(&[vec![Rational::from(2_i64)]], &[Rational::from(1_i64)]);
+        assert_eq!(halves, Solutions::Unique(vec![Rational::new(1, 2)]));
+        assert_eq!(halves.integers(), None);
+    }
+}
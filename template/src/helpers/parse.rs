@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use super::{Grid, Point};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridParseError<E> {
+    Empty,
+    InvalidCell {
+        point: Point,
+        c: char,
+        error: E,
+    },
+    RaggedLine {
+        y: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl<E> Display for GridParseError<E>
+where
+    E: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "grid is empty"),
+            Self::InvalidCell { point, c, error } => {
+                write!(
+                    f,
+                    "invalid cell {c:?} at ({},{}): {error:?}",
+                    point.y, point.x
+                )
+            }
+            Self::RaggedLine { y, expected, found } => {
+                write!(f, "line {y} has {found} cells, expected {expected}")
+            }
+        }
+    }
+}
+
+impl<E> std::error::Error for GridParseError<E> where E: std::fmt::Debug {}
+
+/// Parses grids cell by cell via `TryFrom<char>`, and records where certain
+/// marker characters (like the start `S` or the robot `@`) are on the way.
+#[derive(Debug, Clone)]
+pub struct GridParser<T> {
+    markers: Vec<(char, Option<T>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedGrid<T> {
+    pub grid: Grid<T>,
+    markers: HashMap<char, Vec<Point>>,
+}
+
+impl<T> ParsedGrid<T> {
+    /// All positions of the marker, in reading order.
+    pub fn markers(&self, c: char) -> &[Point] {
+        self.markers.get(&c).map_or(&[], Vec::as_slice)
+    }
+
+    /// The position of a marker that has to appear exactly once.
+    pub fn marker(&self, c: char) -> Option<&Point> {
+        match self.markers(c) {
+            [point] => Some(point),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for GridParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> GridParser<T> {
+    pub fn new() -> Self {
+        Self {
+            markers: Vec::new(),
+        }
+    }
+
+    /// Records the positions of `c`. The cell itself is still parsed as usual.
+    pub fn marker(mut self, c: char) -> Self {
+        self.markers.push((c, None));
+        self
+    }
+
+    /// Records the positions of `c` and puts `cell` into the grid in its place,
+    /// so the cell type does not need a variant for the marker.
+    pub fn marker_as(mut self, c: char, cell: T) -> Self {
+        self.markers.push((c, Some(cell)));
+        self
+    }
+}
+
+impl<T> GridParser<T>
+where
+    T: TryFrom<char> + Clone,
+{
+    /// Blank lines before and after the grid are skipped, like the one left
+    /// over after splitting the input on `"\n\n"`.
+    pub fn parse(&self, input: &str) -> Result<ParsedGrid<T>, GridParseError<T::Error>> {
+        let mut markers: HashMap<char, Vec<Point>> = HashMap::new();
+        let mut inner = Vec::new();
+        let mut width = None;
+
+        let mut lines: Vec<&str> = input.lines().skip_while(|l| l.is_empty()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        for (y, line) in lines.into_iter().enumerate() {
+            let mut found = 0;
+            for (x, c) in line.chars().enumerate() {
+                let point = Point { x, y };
+                let marker = self.markers.iter().find(|(m, _)| *m == c);
+                if marker.is_some() {
+                    markers.entry(c).or_default().push(point);
+                }
+                let cell = match marker {
+                    Some((_, Some(cell))) => cell.clone(),
+                    _ => T::try_from(c).map_err(|error| GridParseError::InvalidCell {
+                        point,
+                        c,
+                        error,
+                    })?,
+                };
+                inner.push(cell);
+                found += 1;
+            }
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(GridParseError::RaggedLine { y, expected, found });
+                }
+                Some(_) => (),
+            }
+        }
+
+        match width {
+            None | Some(0) => Err(GridParseError::Empty),
+            Some(width) => Ok(ParsedGrid {
+                grid: Grid { inner, width },
+                markers,
+            }),
+        }
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: TryFrom<char> + Clone,
+{
+    pub fn try_from_str(input: &str) -> Result<Self, GridParseError<T::Error>> {
+        GridParser::new().parse(input).map(|parsed| parsed.grid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Cell {
+        Wall,
+        Empty,
+    }
+
+    impl TryFrom<char> for Cell {
+        type Error = ();
+
+        fn try_from(value: char) -> Result<Self, Self::Error> {
+            match value {
+                '#' => Ok(Self::Wall),
+                '.' => Ok(Self::Empty),
+                _ => Err(()),
+            }
+        }
+    }
+
+    #[test]
+    fn markers() {
+        let parsed = GridParser::new()
+            .marker_as('S', Cell::Empty)
+            .marker_as('E', Cell::Empty)
+            .parse("####\n#S.#\n#.E#\n####\n")
+            .unwrap();
+        assert_eq!(parsed.marker('S'), Some(&Point { x: 1, y: 1 }));
+        assert_eq!(parsed.marker('E'), Some(&Point { x: 2, y: 2 }));
+        assert_eq!(parsed.marker('@'), None);
+        assert_eq!(parsed.grid.count(|c| *c == Cell::Empty), 4);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Grid::<Cell>::try_from_str("##\n#@\n"),
+            Err(GridParseError::InvalidCell {
+                point: Point { x: 1, y: 1 },
+                c: '@',
+                error: ()
+            })
+        );
+        assert_eq!(
+            Grid::<Cell>::try_from_str("##\n#\n"),
+            Err(GridParseError::RaggedLine {
+                y: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(Grid::<Cell>::try_from_str(""), Err(GridParseError::Empty));
+        assert_eq!(
+            Grid::<Cell>::try_from_str("\n\n"),
+            Err(GridParseError::Empty)
+        );
+    }
+
+    #[test]
+    fn blank_lines_around() {
+        let parsed = GridParser::new()
+            .marker_as('S', Cell::Empty)
+            .parse("\n##\n#S\n\n\n")
+            .unwrap();
+        assert_eq!(parsed.grid.height(), 2);
+        assert_eq!(parsed.marker('S'), Some(&Point { x: 1, y: 1 }));
+        assert_eq!(
+            Grid::<Cell>::try_from_str("##\n\n##\n"),
+            Err(GridParseError::RaggedLine {
+                y: 1,
+                expected: 2,
+                found: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use super::{Grid, Point, Vector};
+
+/// A small grid to search for inside a larger one. `None` cells are wildcards
+/// and match anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern<T> {
+    cells: Grid<Option<T>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    /// Number of clockwise quarter turns, applied after the flip.
+    pub rotation: u8,
+    pub flipped: bool,
+}
+
+impl Orientation {
+    pub const IDENTITY: Self = Self {
+        rotation: 0,
+        flipped: false,
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// Top-left corner of the matched window.
+    pub point: Point,
+    pub orientation: Orientation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordMatch {
+    pub start: Point,
+    pub direction: Vector,
+}
+
+impl<T> Pattern<T> {
+    pub fn new(cells: Grid<Option<T>>) -> Self {
+        Self { cells }
+    }
+
+    pub fn from_str_with<F: Fn(char) -> Option<T> + Copy>(input: &str, f: F) -> Self {
+        Self::new(Grid::from_str_with(input, f))
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+}
+
+impl Pattern<char> {
+    pub fn from_str(input: &str, wildcard: char) -> Self {
+        Self::from_str_with(input, |c| (c != wildcard).then_some(c))
+    }
+}
+
+impl<T> Pattern<T>
+where
+    T: Clone + PartialEq,
+{
+    /// All distinct rotations and reflections of the pattern. Symmetric patterns
+    /// yield fewer than eight entries, so every match is only reported once.
+    pub fn orientations(&self) -> Vec<(Orientation, Self)> {
+        let mut result: Vec<(Orientation, Self)> = Vec::new();
+        for flipped in [false, true] {
+            let mut cells = if flipped {
+                self.cells.clone().flip_horizontal()
+            } else {
+                self.cells.clone()
+            };
+            for rotation in 0..4 {
+                if !result.iter().any(|(_, p)| p.cells == cells) {
+                    result.push((
+                        Orientation { rotation, flipped },
+                        Self {
+                            cells: cells.clone(),
+                        },
+                    ));
+                }
+                cells = cells.rotate_right();
+            }
+        }
+        result
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: PartialEq,
+{
+    fn pattern_matches_at(&self, pattern: &Pattern<T>, origin: &Point) -> bool {
+        pattern.cells.rows().all(|row| {
+            let start = (origin.y + row.y) * self.width + origin.x;
+            self.inner[start..start + row.cells.len()]
+                .iter()
+                .zip(row.cells)
+                .all(|(cell, expected)| expected.as_ref().is_none_or(|e| e == cell))
+        })
+    }
+
+    /// Top-left corners of every window that matches the pattern as-is.
+    pub fn find_pattern(&self, pattern: &Pattern<T>) -> Vec<Point> {
+        let mut result = Vec::new();
+        if pattern.width() > self.width() || pattern.height() > self.height() {
+            return result;
+        }
+        for y in 0..=(self.height() - pattern.height()) {
+            for x in 0..=(self.width() - pattern.width()) {
+                let point = Point { x, y };
+                if self.pattern_matches_at(pattern, &point) {
+                    result.push(point);
+                }
+            }
+        }
+        result
+    }
+
+    /// Searches for the word starting at every cell and going in all eight
+    /// directions.
+    pub fn find_word(&self, word: &[T]) -> Vec<WordMatch> {
+        let mut result = Vec::new();
+        let Some(first) = word.first() else {
+            return result;
+        };
+        for (i, cell) in self.inner.iter().enumerate() {
+            if cell != first {
+                continue;
+            }
+            let start = Point {
+                x: i % self.width,
+                y: i / self.width,
+            };
+            for direction in &Vector::ALL_DIRECTIONS {
+                let mut point = Some(start);
+                let found = word.iter().all(|expected| {
+                    let Some(current) = point.take() else {
+                        return false;
+                    };
+                    let matches = self.get(&current).is_some_and(|cell| cell == expected);
+                    point = current.checked_add(*direction);
+                    matches
+                });
+                if found {
+                    result.push(WordMatch {
+                        start,
+                        direction: *direction,
+                    });
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Clone + PartialEq,
+{
+    pub fn find_pattern_all_orientations(&self, pattern: &Pattern<T>) -> Vec<PatternMatch> {
+        pattern
+            .orientations()
+            .into_iter()
+            .flat_map(|(orientation, pattern)| {
+                self.find_pattern(&pattern)
+                    .into_iter()
+                    .map(move |point| PatternMatch { point, orientation })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
+
+    #[test]
+    fn word_search() {
+        let grid: Grid<char> = Grid::from_str(EXAMPLE);
+        let word: Vec<char> = "XMAS".chars().collect();
+        assert_eq!(grid.find_word(&word).len(), 18);
+    }
+
+    #[test]
+    fn cross_pattern() {
+        let grid: Grid<char> = Grid::from_str(EXAMPLE);
+        let pattern = Pattern::from_str("M.S\n.A.\nM.S", '.');
+        assert_eq!(pattern.orientations().len(), 4);
+        assert_eq!(grid.find_pattern_all_orientations(&pattern).len(), 9);
+    }
+
+    #[test]
+    fn rotation() {
+        let grid: Grid<char> = Grid::from_str("ab\ncd\nef");
+        let rotated = grid.rotate_right();
+        assert_eq!(rotated.to_string(), "eca\nfdb\n");
+    }
+}
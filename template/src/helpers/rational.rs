@@ -0,0 +1,272 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::coordinate::gcd;
+
+/// An exact fraction, always reduced and with a positive denominator, so the
+/// derived `Eq` and `Hash` work. Arithmetic panics on overflow instead of
+/// wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "denominator must not be zero");
+        let divisor = gcd(numerator, denominator);
+        let (numerator, denominator) = (div(numerator, divisor), div(denominator, divisor));
+        if denominator < 0 {
+            Self {
+                numerator: neg(numerator),
+                denominator: neg(denominator),
+            }
+        } else {
+            Self {
+                numerator,
+                denominator,
+            }
+        }
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub const fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub const fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub const fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value, if it is a whole number.
+    pub const fn to_integer(self) -> Option<i128> {
+        if self.is_integer() {
+            Some(self.numerator)
+        } else {
+            None
+        }
+    }
+
+    pub const fn floor(&self) -> i128 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        neg(neg(self.numerator).div_euclid(self.denominator))
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            numerator: self.numerator.checked_abs().expect("rational overflow"),
+            denominator: self.denominator,
+        }
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.denominator, self.numerator)
+    }
+}
+
+fn mul(a: i128, b: i128) -> i128 {
+    a.checked_mul(b).expect("rational overflow")
+}
+
+fn div(a: i128, b: i128) -> i128 {
+    a.checked_div(b).expect("rational overflow")
+}
+
+fn neg(a: i128) -> i128 {
+    a.checked_neg().expect("rational overflow")
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::integer(i128::from(value))
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        mul(self.numerator, other.denominator).cmp(&mul(other.numerator, self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let divisor = gcd(self.denominator, other.denominator);
+        let (left, right) = (self.denominator / divisor, other.denominator / divisor);
+        let numerator = mul(self.numerator, right)
+            .checked_add(mul(other.numerator, left))
+            .expect("rational overflow");
+        Self::new(numerator, mul(self.denominator, right))
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        // cancel crosswise first, so the products stay small
+        let first = gcd(self.numerator, other.denominator);
+        let second = gcd(other.numerator, self.denominator);
+        Self::new(
+            mul(self.numerator / first, other.numerator / second),
+            mul(self.denominator / second, other.denominator / first),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Mul::mul(self, other.recip())
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            numerator: neg(self.numerator),
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for Rational {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl Sum for Rational {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let third = Rational::new(2, -6);
+        assert_eq!((third.numerator(), third.denominator()), (-1, 3));
+        assert_eq!(third + Rational::new(1, 2), Rational::new(1, 6));
+        assert_eq!(third * Rational::from(-3_i64), Rational::ONE);
+        assert_eq!(
+            Rational::new(3, 4) / Rational::new(3, 8),
+            Rational::from(2_i64)
+        );
+        assert_eq!(third.floor(), -1);
+        assert_eq!(third.ceil(), 0);
+        assert!(third < Rational::ZERO);
+        assert_eq!(third.to_integer(), None);
+        assert_eq!(third.to_string(), "-1/3");
+        assert_eq!(
+            [
+                Rational::new(1, 2),
+                Rational::new(1, 3),
+                Rational::new(1, 6)
+            ]
+            .into_iter()
+            .sum::<Rational>(),
+            Rational::ONE
+        );
+        assert_eq!(
+            Rational::new(i128::MAX, -1).abs(),
+            Rational::from(i128::MAX)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "rational overflow")]
+    fn negating_min() {
+        let _ = -Rational::from(i128::MIN);
+    }
+
+    #[test]
+    #[should_panic(expected = "rational overflow")]
+    fn dividing_min() {
+        let _ = Rational::new(i128::MIN, -1);
+    }
+}
@@ -0,0 +1,181 @@
+use super::{Grid, Point, Vector};
+
+/// The cells along a vector, until the grid ends.
+#[derive(Debug, Clone)]
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    next: Option<Point>,
+    direction: Vector,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (Point, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.next.take()?;
+        let cell = self.grid.get(&point)?;
+        self.next = point.checked_add(self.direction);
+        Some((point, cell))
+    }
+}
+
+impl<T> Grid<T> {
+    fn ray_from(&self, first: Option<Point>, direction: Vector) -> Ray<'_, T> {
+        assert!(direction != Vector { x: 0, y: 0 }, "ray needs a direction");
+        Ray {
+            grid: self,
+            next: first,
+            direction,
+        }
+    }
+
+    /// The cells at `start + direction`, `start + 2 * direction` and so on.
+    /// `start` itself is not included.
+    pub fn ray(&self, start: &Point, direction: Vector) -> Ray<'_, T> {
+        self.ray_from(start.checked_add(direction), direction)
+    }
+
+    /// Like [`Grid::ray`] against the direction.
+    pub fn ray_back(&self, start: &Point, direction: Vector) -> Ray<'_, T> {
+        self.ray(start, -direction)
+    }
+
+    /// The cells of the ray before the first one where `stop` holds.
+    pub fn ray_until<'a>(
+        &'a self,
+        start: &Point,
+        direction: Vector,
+        stop: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = (Point, &'a T)> {
+        self.ray(start, direction)
+            .take_while(move |(_, cell)| !stop(cell))
+    }
+
+    /// The first cell of the ray where `hit` holds.
+    pub fn first_hit(
+        &self,
+        start: &Point,
+        direction: Vector,
+        hit: impl Fn(&T) -> bool,
+    ) -> Option<(Point, &T)> {
+        self.ray(start, direction).find(|(_, cell)| hit(cell))
+    }
+
+    /// Every cell on the line through `point` in steps of `direction`, from
+    /// one edge of the grid to the other. Includes `point` itself.
+    pub fn line_through(&self, point: &Point, direction: Vector) -> Ray<'_, T> {
+        let mut first = *point;
+        while let Some(previous) = first.checked_sub(direction).filter(|p| self.contains(p)) {
+            first = previous;
+        }
+        self.ray_from(Some(first).filter(|p| self.contains(p)), direction)
+    }
+
+    /// Whether no cell strictly between `a` and `b` is blocked. Only the
+    /// cells the exact line passes through count, which are the multiples of
+    /// the reduced vector from `a` to `b`.
+    pub fn line_of_sight(&self, a: &Point, b: &Point, blocked: impl Fn(&T) -> bool) -> bool {
+        if a == b {
+            return true;
+        }
+        let step = (*b - *a).normalized();
+        self.ray(a, step)
+            .take_while(|(point, _)| point != b)
+            .all(|(_, cell)| !blocked(cell))
+    }
+}
+
+/// The cells of the segment from `a` to `b` as drawn by Bresenham's
+/// algorithm, both ends included.
+pub fn bresenham(a: Point<i64>, b: Point<i64>) -> impl Iterator<Item = Point<i64>> {
+    let (dx, dy) = ((b.x - a.x).abs(), -(b.y - a.y).abs());
+    let (sx, sy) = ((b.x - a.x).signum(), (b.y - a.y).signum());
+    let mut error = dx + dy;
+    let mut next = Some(a);
+    std::iter::from_fn(move || {
+        let point = next?;
+        next = (point != b).then(|| {
+            let (mut p, doubled) = (point, 2 * error);
+            if doubled >= dy {
+                error += dy;
+                p.x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                p.y += sy;
+            }
+            p
+        });
+        Some(point)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rays() {
+        let grid: Grid<char> = Grid::from_str("#.....\n.O.O..\n......\n...#..\n");
+        let start = Point { x: 0, y: 1 };
+        let right = Vector { x: 1, y: 0 };
+        assert_eq!(grid.ray(&start, right).count(), 5);
+        assert_eq!(
+            grid.first_hit(&start, right, |c| *c == 'O'),
+            Some((Point { x: 1, y: 1 }, &'O'))
+        );
+        assert_eq!(grid.ray_until(&start, right, |c| *c == 'O').count(), 0);
+        assert_eq!(
+            grid.ray_back(&Point { x: 3, y: 3 }, Vector { x: 1, y: 1 })
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>(),
+            [
+                Point { x: 2, y: 2 },
+                Point { x: 1, y: 1 },
+                Point { x: 0, y: 0 }
+            ]
+        );
+
+        // antinodes in line with two antennas, like 2024 day 8
+        let antinodes: Vec<Point> = grid
+            .line_through(
+                &Point { x: 1, y: 1 },
+                Point::<usize> { x: 3, y: 1 } - Point { x: 1, y: 1 },
+            )
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(
+            antinodes,
+            [
+                Point { x: 1, y: 1 },
+                Point { x: 3, y: 1 },
+                Point { x: 5, y: 1 }
+            ]
+        );
+
+        let wall = |c: &char| *c == '#';
+        assert!(grid.line_of_sight(&Point { x: 5, y: 0 }, &Point { x: 1, y: 2 }, wall));
+        assert!(!grid.line_of_sight(&Point { x: 1, y: 3 }, &Point { x: 5, y: 3 }, wall));
+        assert!(!grid.line_of_sight(&Point { x: 3, y: 2 }, &Point { x: 3, y: 0 }, |c| *c == 'O'));
+    }
+
+    #[test]
+    fn segments() {
+        let points: Vec<Point<i64>> =
+            bresenham(Point { x: 0, y: 0 }, Point { x: 4, y: -2 }).collect();
+        assert_eq!(
+            points,
+            [
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: -1 },
+                Point { x: 2, y: -1 },
+                Point { x: 3, y: -2 },
+                Point { x: 4, y: -2 },
+            ]
+        );
+        assert_eq!(
+            bresenham(Point { x: 3, y: 3 }, Point { x: 3, y: 3 }).count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,442 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use super::image::Rgb;
+use super::terminal::{self, Key, RawMode};
+use super::{Grid, Point};
+
+/// Collects snapshots of a simulation. Frames are passed in as closures, so
+/// when recording is disabled nothing gets cloned or allocated at all.
+#[derive(Debug, Clone)]
+pub struct Recorder<T> {
+    frames: Option<Vec<Grid<T>>>,
+}
+
+impl<T> Recorder<T> {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            frames: enabled.then(Vec::new),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(false)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.frames.is_some()
+    }
+
+    pub fn record(&mut self, frame: impl FnOnce() -> Grid<T>) {
+        if let Some(frames) = &mut self.frames {
+            frames.push(frame());
+        }
+    }
+
+    pub fn frames(&self) -> &[Grid<T>] {
+        self.frames.as_deref().unwrap_or(&[])
+    }
+}
+
+impl Recorder<bool> {
+    pub fn record_points<'a, I>(&mut self, width: usize, height: usize, points: impl FnOnce() -> I)
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        self.record(|| {
+            let mut grid = Grid::new(width, height, false);
+            for point in points() {
+                grid[point] = true;
+            }
+            grid
+        });
+    }
+}
+
+impl<T> Recorder<T> {
+    /// Writes all frames as a looping animated GIF. Every cell becomes a
+    /// `scale` x `scale` square and every frame is shown for `delay`
+    /// hundredths of a second. At most 256 distinct colors are supported.
+    pub fn write_gif(
+        &self,
+        mut w: impl Write,
+        scale: usize,
+        delay: u16,
+        color: impl Fn(&T) -> Rgb,
+    ) -> io::Result<()> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_owned());
+        let Some(first) = self.frames().first() else {
+            return Err(invalid("no frames recorded"));
+        };
+        let width = u16::try_from(first.width() * scale).map_err(|_| invalid("image too wide"))?;
+        let height =
+            u16::try_from(first.height() * scale).map_err(|_| invalid("image too high"))?;
+
+        let mut palette: Vec<Rgb> = Vec::new();
+        let mut index: HashMap<Rgb, u8> = HashMap::new();
+        let mut frames = Vec::new();
+        for frame in self.frames() {
+            if (frame.width(), frame.height()) != (first.width(), first.height()) {
+                return Err(invalid("frame sizes differ"));
+            }
+            let mut pixels = Vec::with_capacity(usize::from(width) * usize::from(height));
+            for row in frame.rows() {
+                let mut line = Vec::with_capacity(usize::from(width));
+                for cell in row.cells {
+                    let rgb = color(cell);
+                    let i = if let Some(i) = index.get(&rgb) {
+                        *i
+                    } else {
+                        let i = u8::try_from(palette.len())
+                            .map_err(|_| invalid("more than 256 colors"))?;
+                        palette.push(rgb);
+                        index.insert(rgb, i);
+                        i
+                    };
+                    line.extend(std::iter::repeat_n(i, scale));
+                }
+                for _ in 0..scale {
+                    pixels.extend(&line);
+                }
+            }
+            frames.push(pixels);
+        }
+
+        // the color table size is always a power of two, at least 4 entries
+        let bits = (palette.len().max(4) - 1).ilog2() + 1;
+        let table_size = 1 << bits;
+        palette.resize(table_size, Rgb::BLACK);
+
+        w.write_all(b"GIF89a")?;
+        w.write_all(&width.to_le_bytes())?;
+        w.write_all(&height.to_le_bytes())?;
+        w.write_all(&[0xf0 | u8::try_from(bits - 1).unwrap(), 0, 0])?;
+        for rgb in &palette {
+            w.write_all(&[rgb.0, rgb.1, rgb.2])?;
+        }
+        // loop forever
+        w.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+        for pixels in frames {
+            w.write_all(&[0x21, 0xf9, 0x04, 0x00])?;
+            w.write_all(&delay.to_le_bytes())?;
+            w.write_all(&[0x00, 0x00])?;
+
+            w.write_all(&[0x2c, 0, 0, 0, 0])?;
+            w.write_all(&width.to_le_bytes())?;
+            w.write_all(&height.to_le_bytes())?;
+            w.write_all(&[0x00])?;
+
+            let min_code_size = u8::try_from(bits).unwrap();
+            w.write_all(&[min_code_size])?;
+            for block in lzw_encode(&pixels, min_code_size).chunks(255) {
+                w.write_all(&[u8::try_from(block.len()).unwrap()])?;
+                w.write_all(block)?;
+            }
+            w.write_all(&[0x00])?;
+        }
+        w.write_all(&[0x3b])
+    }
+}
+
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u32) {
+        self.buffer |= u32::from(code) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.out.push(self.buffer.to_le_bytes()[0]);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.out.push(self.buffer.to_le_bytes()[0]);
+        }
+        self.out
+    }
+}
+
+fn lzw_encode(pixels: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4096;
+
+    let clear: u16 = 1 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter {
+        out: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut size = u32::from(min_code_size) + 1;
+
+    writer.write(clear, size);
+    let Some((&first, rest)) = pixels.split_first() else {
+        writer.write(end, size);
+        return writer.finish();
+    };
+    let mut current = u16::from(first);
+    for &pixel in rest {
+        if let Some(&code) = table.get(&(current, pixel)) {
+            current = code;
+            continue;
+        }
+        writer.write(current, size);
+        if next < MAX_CODE {
+            table.insert((current, pixel), next);
+            next += 1;
+            // the decoder lags one entry behind, so it widens one code later
+            if u32::from(next) > (1 << size) && size < 12 {
+                size += 1;
+            }
+        } else {
+            writer.write(clear, size);
+            table.clear();
+            next = end + 1;
+            size = u32::from(min_code_size) + 1;
+        }
+        current = u16::from(pixel);
+    }
+    writer.write(current, size);
+    writer.write(end, size);
+    writer.finish()
+}
+
+/// Playback position and speed, separate from the terminal handling.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playback {
+    pub frame: usize,
+    pub frames: usize,
+    pub playing: bool,
+    pub fps: f64,
+    pub quit: bool,
+}
+
+impl Playback {
+    pub fn new(frames: usize) -> Self {
+        Self {
+            frame: 0,
+            frames,
+            playing: true,
+            fps: 10.0,
+            quit: false,
+        }
+    }
+
+    /// Space pauses, arrows step (and pause), `+`/`-` change the speed, `g`/`G`
+    /// seek to the start/end, `[`/`]` seek by a tenth, and `q` quits.
+    pub fn handle(&mut self, key: Key) {
+        let last = self.frames.saturating_sub(1);
+        let jump = (self.frames / 10).max(1);
+        match key {
+            Key::Char(' ') => self.playing = !self.playing,
+            Key::Right | Key::Char('l') => {
+                self.playing = false;
+                self.frame = (self.frame + 1).min(last);
+            }
+            Key::Left | Key::Char('h') => {
+                self.playing = false;
+                self.frame = self.frame.saturating_sub(1);
+            }
+            Key::Char('+') => self.fps = (self.fps * 2.0).min(1000.0),
+            Key::Char('-') => self.fps = (self.fps / 2.0).max(0.25),
+            Key::Char('g') => self.frame = 0,
+            Key::Char('G') => self.frame = last,
+            Key::Char(']') => self.frame = (self.frame + jump).min(last),
+            Key::Char('[') => self.frame = self.frame.saturating_sub(jump),
+            Key::Char('q') | Key::Escape => self.quit = true,
+            _ => (),
+        }
+    }
+
+    /// Advances by one frame if playing. Stops at the last frame.
+    pub fn tick(&mut self) {
+        self.advance(1);
+    }
+
+    /// Advances by `frames` frames if playing, e.g. all frames that were due
+    /// since the last redraw. Stops at the last frame.
+    pub fn advance(&mut self, frames: usize) {
+        if self.playing {
+            let last = self.frames.saturating_sub(1);
+            if self.frame + frames < last {
+                self.frame += frames;
+            } else {
+                self.frame = last;
+                self.playing = false;
+            }
+        }
+    }
+}
+
+impl<T> Recorder<T>
+where
+    T: Display,
+{
+    /// Plays the frames back in the terminal, see [`Playback::handle`] for the
+    /// keys.
+    pub fn play(&self) -> io::Result<()> {
+        let frames = self.frames();
+        if frames.is_empty() {
+            return Ok(());
+        }
+        let raw = RawMode::enable()?;
+        let mut stdout = io::stdout();
+        let mut playback = Playback::new(frames.len());
+        let mut last_tick = Instant::now();
+
+        while !playback.quit {
+            terminal::clear_screen(&mut stdout)?;
+            write!(stdout, "{}", frames[playback.frame])?;
+            writeln!(
+                stdout,
+                "frame {}/{}  {:.2} fps  {}",
+                playback.frame + 1,
+                frames.len(),
+                playback.fps,
+                if playback.playing {
+                    "playing"
+                } else {
+                    "paused"
+                }
+            )?;
+            stdout.flush()?;
+
+            if let Some(key) = raw.read_key()? {
+                playback.handle(key);
+            }
+            // reading a key waits up to a tenth of a second, so faster
+            // speeds play several frames per redraw
+            let frame = Duration::from_secs_f64(1.0 / playback.fps);
+            if playback.playing {
+                let due = u32::try_from(last_tick.elapsed().as_nanos() / frame.as_nanos())
+                    .unwrap_or(u32::MAX);
+                playback.advance(usize::try_from(due).unwrap());
+                last_tick += frame * due;
+            } else {
+                last_tick = Instant::now();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1_usize << min_code_size;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            *table = (0..clear).map(|i| vec![u8::try_from(i).unwrap()]).collect();
+            table.push(Vec::new());
+            table.push(Vec::new());
+        };
+        reset(&mut table);
+        let mut size = usize::from(min_code_size) + 1;
+        let (mut bit, mut out, mut prev): (usize, Vec<u8>, Option<Vec<u8>>) = (0, Vec::new(), None);
+        loop {
+            let code = (0..size).fold(0, |code, i| {
+                code | (usize::from(data[(bit + i) / 8] >> ((bit + i) % 8) & 1) << i)
+            });
+            bit += size;
+            if code == clear {
+                reset(&mut table);
+                size = usize::from(min_code_size) + 1;
+                prev = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+            let entry = match (&prev, table.get(code)) {
+                (_, Some(entry)) => entry.clone(),
+                (Some(prev), None) => [prev.clone(), vec![prev[0]]].concat(),
+                (None, None) => panic!("invalid code"),
+            };
+            if let Some(prev) = prev {
+                table.push([prev, vec![entry[0]]].concat());
+                if table.len() == 1 << size && size < 12 {
+                    size += 1;
+                }
+            }
+            out.extend(&entry);
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_roundtrip() {
+        let pixels: Vec<u8> = (0..20_000_u32)
+            .map(|i| u8::try_from((i * i / 7) % 5).unwrap())
+            .collect();
+        assert_eq!(lzw_decode(&lzw_encode(&pixels, 3), 3), pixels);
+        let pixels = vec![0; 10_000];
+        assert_eq!(lzw_decode(&lzw_encode(&pixels, 2), 2), pixels);
+    }
+
+    #[test]
+    fn disabled_is_free() {
+        let mut recorder: Recorder<bool> = Recorder::disabled();
+        recorder.record(|| unreachable!("frame must not be built"));
+        assert!(recorder.frames().is_empty());
+    }
+
+    #[test]
+    fn gif() {
+        let mut recorder = Recorder::new(true);
+        for i in 0..3 {
+            let points = [Point { x: i, y: 1 }];
+            recorder.record_points(4, 3, || &points);
+        }
+        assert_eq!(recorder.frames().len(), 3);
+        let mut gif = Vec::new();
+        recorder
+            .write_gif(
+                &mut gif,
+                2,
+                10,
+                |set| if *set { Rgb::WHITE } else { Rgb::BLACK },
+            )
+            .unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(&gif[6..10], &[8, 0, 6, 0]);
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+
+    #[test]
+    fn playback_controls() {
+        let mut playback = Playback::new(20);
+        playback.tick();
+        assert_eq!(playback.frame, 1);
+        playback.handle(Key::Char(' '));
+        playback.tick();
+        assert_eq!(playback.frame, 1);
+        playback.handle(Key::Right);
+        playback.handle(Key::Char(']'));
+        assert_eq!(playback.frame, 4);
+        playback.handle(Key::Char('G'));
+        playback.handle(Key::Char(' '));
+        playback.tick();
+        assert_eq!((playback.frame, playback.playing), (19, false));
+        playback.handle(Key::Char('g'));
+        playback.handle(Key::Char(' '));
+        playback.advance(7);
+        assert_eq!(playback.frame, 7);
+        playback.advance(100);
+        assert_eq!((playback.frame, playback.playing), (19, false));
+        playback.handle(Key::Char('q'));
+        assert!(playback.quit);
+    }
+}
@@ -0,0 +1,285 @@
+use std::cmp::Ordering::{Equal, Greater, Less};
+use std::fmt::{Display, Write};
+use std::io::IsTerminal;
+
+use super::regions::Labeling;
+use super::{BoundingBox, Grid, Path, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    const PALETTE: [Self; 6] = [
+        Self::Red,
+        Self::Green,
+        Self::Yellow,
+        Self::Blue,
+        Self::Magenta,
+        Self::Cyan,
+    ];
+
+    fn foreground(self) -> u8 {
+        match self {
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::White => 37,
+            Self::Gray => 90,
+        }
+    }
+
+    fn background(self) -> u8 {
+        self.foreground() + 10
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Style {
+    glyph: Option<char>,
+    foreground: Option<Color>,
+    background: Option<Color>,
+}
+
+enum Overlay<'a> {
+    Path(&'a Path, Color),
+    Points(Vec<Point>, Option<char>, Color),
+    Regions(&'a Labeling),
+}
+
+/// Draws a grid with overlays on top, for debugging. Colors are only used
+/// when stdout is a terminal, unless forced with [`Renderer::color`]. Later
+/// overlays are drawn over earlier ones.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    overlays: Vec<Overlay<'a>>,
+    rulers: bool,
+    viewport: Option<BoundingBox>,
+    color: bool,
+}
+
+fn arrow(from: &Point, to: &Point) -> char {
+    match (to.x.cmp(&from.x), to.y.cmp(&from.y)) {
+        (Greater, Equal) => '→',
+        (Less, Equal) => '←',
+        (Equal, Greater) => '↓',
+        (Equal, Less) => '↑',
+        (Greater, Greater) => '↘',
+        (Greater, Less) => '↗',
+        (Less, Greater) => '↙',
+        (Less, Less) => '↖',
+        (Equal, Equal) => '•',
+    }
+}
+
+impl<'a, T> Renderer<'a, T> {
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        Self {
+            grid,
+            overlays: Vec::new(),
+            rulers: false,
+            viewport: None,
+            color: std::io::stdout().is_terminal(),
+        }
+    }
+
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    /// Draws the path with arrows pointing to the next step.
+    pub fn path(mut self, path: &'a Path, color: Color) -> Self {
+        self.overlays.push(Overlay::Path(path, color));
+        self
+    }
+
+    /// Highlights the points, optionally replacing the cell with `glyph`.
+    pub fn points<'p>(
+        mut self,
+        points: impl IntoIterator<Item = &'p Point>,
+        glyph: Option<char>,
+        color: Color,
+    ) -> Self {
+        self.overlays.push(Overlay::Points(
+            points.into_iter().copied().collect(),
+            glyph,
+            color,
+        ));
+        self
+    }
+
+    /// Gives every region its own background color.
+    pub fn regions(mut self, labeling: &'a Labeling) -> Self {
+        self.overlays.push(Overlay::Regions(labeling));
+        self
+    }
+
+    pub fn rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+
+    pub fn crop(mut self, viewport: BoundingBox) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    fn styles(&self) -> Grid<Style> {
+        let mut styles = Grid::new(self.grid.width(), self.grid.height(), Style::default());
+        for overlay in &self.overlays {
+            match overlay {
+                Overlay::Path(path, color) => {
+                    for (i, point) in path.0.iter().enumerate() {
+                        if let Some(style) = styles.get_mut(point) {
+                            style.glyph =
+                                Some(path.0.get(i + 1).map_or('•', |next| arrow(point, next)));
+                            style.foreground = Some(*color);
+                        }
+                    }
+                }
+                Overlay::Points(points, glyph, color) => {
+                    for point in points {
+                        if let Some(style) = styles.get_mut(point) {
+                            style.glyph = glyph.or(style.glyph);
+                            style.foreground = Some(*color);
+                        }
+                    }
+                }
+                Overlay::Regions(labeling) => {
+                    for (point, style) in &mut styles {
+                        if let Some(label) = labeling.label(&point) {
+                            style.background = Some(Color::PALETTE[label % Color::PALETTE.len()]);
+                        }
+                    }
+                }
+            }
+        }
+        styles
+    }
+}
+
+impl<T> Display for Renderer<'_, T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let styles = self.styles();
+        let full = BoundingBox {
+            min: Point { x: 0, y: 0 },
+            max: Point {
+                x: self.grid.width() - 1,
+                y: self.grid.height() - 1,
+            },
+        };
+        let viewport = self.viewport.as_ref().unwrap_or(&full);
+        let min_x = viewport.min.x;
+        let max_x = viewport.max.x.min(full.max.x);
+        let max_y = viewport.max.y.min(full.max.y);
+        let margin = max_y.to_string().len();
+
+        if self.rulers {
+            for digit in (0..max_x.to_string().len()).rev() {
+                write!(f, "{:margin$} ", "")?;
+                for x in min_x..=max_x {
+                    let place = 10_usize.pow(u32::try_from(digit).unwrap());
+                    if digit == 0 || x % 10 == 0 {
+                        write!(f, "{}", x / place % 10)?;
+                    } else {
+                        write!(f, " ")?;
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+
+        let mut cell = String::new();
+        for y in viewport.min.y..=max_y {
+            if self.rulers {
+                write!(f, "{y:>margin$} ")?;
+            }
+            for x in min_x..=max_x {
+                let point = Point { x, y };
+                let style = styles[&point];
+                cell.clear();
+                match style.glyph {
+                    Some(glyph) => cell.push(glyph),
+                    None => write!(cell, "{}", self.grid[&point])?,
+                }
+                if self.color && (style.foreground.is_some() || style.background.is_some()) {
+                    let mut codes = Vec::new();
+                    if let Some(color) = style.foreground {
+                        codes.push(color.foreground().to_string());
+                    }
+                    if let Some(color) = style.background {
+                        codes.push(color.background().to_string());
+                    }
+                    write!(f, "\x1b[{}m{cell}\x1b[0m", codes.join(";"))?;
+                } else {
+                    write!(f, "{cell}")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_overlays() {
+        let grid: Grid<char> = Grid::from_str("....\n.##.\n....\n");
+        let path = Path(vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 2, y: 0 },
+            Point { x: 3, y: 0 },
+            Point { x: 3, y: 1 },
+        ]);
+        let rendered = Renderer::new(&grid)
+            .color(false)
+            .path(&path, Color::Green)
+            .points(&[Point { x: 0, y: 2 }], Some('X'), Color::Red)
+            .to_string();
+        assert_eq!(rendered, "→→→↓\n.##•\nX...\n");
+    }
+
+    #[test]
+    fn rulers_and_crop() {
+        let grid: Grid<char> = Grid::from_str("............\n");
+        let rendered = Renderer::new(&grid)
+            .color(false)
+            .rulers()
+            .crop(BoundingBox {
+                min: Point { x: 8, y: 0 },
+                max: Point { x: 11, y: 0 },
+            })
+            .to_string();
+        assert_eq!(rendered, "    1 \n  8901\n0 ....\n");
+    }
+
+    #[test]
+    fn colors() {
+        let grid: Grid<char> = Grid::from_str("ab\n");
+        let labeling = grid.regions();
+        let rendered = Renderer::new(&grid)
+            .color(true)
+            .regions(&labeling)
+            .to_string();
+        assert_eq!(rendered, "\x1b[41ma\x1b[0m\x1b[42mb\x1b[0m\n");
+    }
+}
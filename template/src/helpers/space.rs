@@ -0,0 +1,446 @@
+use std::collections::VecDeque;
+
+use super::coordinate::{Coordinate, Signed};
+use super::Neighborhood;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T = usize> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// Same overflow behavior as [`super::Vector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Vector3<T = isize> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vector3<T>
+where
+    T: Signed,
+{
+    /// The 6 unit vectors along the axes for [`Neighborhood::Orthogonal`],
+    /// or all 26 offsets to cells sharing a face, edge or corner for
+    /// [`Neighborhood::All`].
+    pub fn offsets(neighborhood: Neighborhood) -> impl Iterator<Item = Self> {
+        let units = [T::NEG_ONE, T::ZERO, T::ONE];
+        (0..27)
+            .map(move |i| Self {
+                x: units[i % 3],
+                y: units[i / 3 % 3],
+                z: units[i / 9],
+            })
+            .filter(move |v| match neighborhood {
+                Neighborhood::Orthogonal => v.manhattan() == T::Distance::ONE,
+                Neighborhood::All => *v != Self::default(),
+            })
+    }
+
+    pub fn manhattan(self) -> T::Distance {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    pub fn chebyshev(self) -> T::Distance {
+        self.x
+            .unsigned_abs()
+            .max(self.y.unsigned_abs())
+            .max(self.z.unsigned_abs())
+    }
+
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+            z: self.z.signum(),
+        }
+    }
+
+    pub fn checked_add(self, v: Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add(v.x)?,
+            y: self.y.checked_add(v.y)?,
+            z: self.z.checked_add(v.z)?,
+        })
+    }
+
+    pub fn checked_sub(self, v: Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_sub(v.x)?,
+            y: self.y.checked_sub(v.y)?,
+            z: self.z.checked_sub(v.z)?,
+        })
+    }
+
+    pub fn checked_mul(self, m: T) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_mul(m)?,
+            y: self.y.checked_mul(m)?,
+            z: self.z.checked_mul(m)?,
+        })
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_neg()?,
+            y: self.y.checked_neg()?,
+            z: self.z.checked_neg()?,
+        })
+    }
+}
+
+impl<T: Signed> Default for Vector3<T> {
+    fn default() -> Self {
+        Self {
+            x: T::ZERO,
+            y: T::ZERO,
+            z: T::ZERO,
+        }
+    }
+}
+
+impl<T: Signed> std::ops::Add for Vector3<T> {
+    type Output = Self;
+
+    fn add(self, v: Self) -> Self {
+        self.checked_add(v).expect("vector addition overflowed")
+    }
+}
+
+impl<T: Signed> std::ops::Sub for Vector3<T> {
+    type Output = Self;
+
+    fn sub(self, v: Self) -> Self {
+        self.checked_sub(v).expect("vector subtraction overflowed")
+    }
+}
+
+impl<T: Signed> std::ops::Mul<T> for Vector3<T> {
+    type Output = Self;
+
+    fn mul(self, m: T) -> Self {
+        self.checked_mul(m)
+            .expect("vector multiplication overflowed")
+    }
+}
+
+impl<T: Signed> std::ops::Neg for Vector3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("vector negation overflowed")
+    }
+}
+
+impl<T> Point3<T>
+where
+    T: Coordinate,
+{
+    /// `None` if the result does not fit in `T`.
+    pub fn checked_add(self, v: Vector3<T::Delta>) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_delta(v.x)?,
+            y: self.y.checked_add_delta(v.y)?,
+            z: self.z.checked_add_delta(v.z)?,
+        })
+    }
+
+    pub fn checked_vector_to(self, p: Self) -> Option<Vector3<T::Delta>> {
+        Some(Vector3 {
+            x: self.x.checked_delta_to(p.x)?,
+            y: self.y.checked_delta_to(p.y)?,
+            z: self.z.checked_delta_to(p.z)?,
+        })
+    }
+
+    pub fn manhattan(self, p: Self) -> T::Distance {
+        self.x.abs_diff(p.x) + self.y.abs_diff(p.y) + self.z.abs_diff(p.z)
+    }
+
+    pub fn chebyshev(self, p: Self) -> T::Distance {
+        self.x
+            .abs_diff(p.x)
+            .max(self.y.abs_diff(p.y))
+            .max(self.z.abs_diff(p.z))
+    }
+
+    pub fn neighbors_in(self, neighborhood: Neighborhood) -> impl Iterator<Item = Self> {
+        Vector3::offsets(neighborhood).filter_map(move |v| self.checked_add(v))
+    }
+}
+
+impl<T: Coordinate> std::ops::Add<Vector3<T::Delta>> for Point3<T> {
+    type Output = Self;
+
+    fn add(self, v: Vector3<T::Delta>) -> Self {
+        self.checked_add(v).expect("point out of range")
+    }
+}
+
+/// `b - a` is the vector from `a` to `b`.
+impl<T: Coordinate> std::ops::Sub for Point3<T> {
+    type Output = Vector3<T::Delta>;
+
+    fn sub(self, p: Self) -> Vector3<T::Delta> {
+        p.checked_vector_to(self)
+            .expect("vector between points overflowed")
+    }
+}
+
+/// Inclusive on both ends.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoundingBox3<T = usize> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T> BoundingBox3<T>
+where
+    T: Coordinate,
+{
+    pub fn from_point(point: &Point3<T>) -> Self {
+        Self {
+            min: *point,
+            max: *point,
+        }
+    }
+
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point3<T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let mut bbox = Self::from_point(points.next()?);
+        for point in points {
+            bbox.extend(point);
+        }
+        Some(bbox)
+    }
+
+    pub fn extend(&mut self, point: &Point3<T>) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.min.z = self.min.z.min(point.z);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+        self.max.z = self.max.z.max(point.z);
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+
+    pub fn depth(&self) -> T {
+        self.max.z - self.min.z + T::ONE
+    }
+
+    pub fn volume(&self) -> T {
+        self.width() * self.height() * self.depth()
+    }
+
+    pub fn contains(&self, point: &Point3<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+}
+
+/// A dense 3D grid, stored x first, then y, then z.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoxelGrid<T> {
+    inner: Vec<T>,
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl<T> VoxelGrid<T> {
+    pub fn new(width: usize, height: usize, depth: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            inner: vec![value; width * height * depth],
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn index_of(&self, point: &Point3) -> Option<usize> {
+        self.contains(point)
+            .then(|| (point.z * self.height + point.y) * self.width + point.x)
+    }
+
+    fn point_of(&self, index: usize) -> Point3 {
+        Point3 {
+            x: index % self.width,
+            y: index / self.width % self.height,
+            z: index / (self.width * self.height),
+        }
+    }
+
+    pub fn contains(&self, point: &Point3) -> bool {
+        point.x < self.width && point.y < self.height && point.z < self.depth
+    }
+
+    pub fn get(&self, point: &Point3) -> Option<&T> {
+        self.index_of(point).map(|i| &self.inner[i])
+    }
+
+    pub fn get_mut(&mut self, point: &Point3) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.inner[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point3, &T)> {
+        self.inner
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.point_of(i), cell))
+    }
+
+    /// The points on the six outer faces of the grid.
+    pub fn boundary(&self) -> impl Iterator<Item = Point3> + use<'_, T> {
+        (0..self.inner.len()).map(|i| self.point_of(i)).filter(|p| {
+            p.x == 0
+                || p.y == 0
+                || p.z == 0
+                || p.x + 1 == self.width
+                || p.y + 1 == self.height
+                || p.z + 1 == self.depth
+        })
+    }
+
+    /// Marks every cell reachable from any of `starts` through passable
+    /// cells. Starts that are not passable are ignored.
+    pub fn flood_fill(
+        &self,
+        starts: impl IntoIterator<Item = Point3>,
+        neighborhood: Neighborhood,
+        passable: impl Fn(&T) -> bool,
+    ) -> VoxelGrid<bool> {
+        let mut reached = VoxelGrid::new(self.width, self.height, self.depth, false);
+        let mut queue: VecDeque<Point3> = VecDeque::new();
+        for start in starts {
+            if self.get(&start).is_some_and(&passable) && !reached[&start] {
+                reached[&start] = true;
+                queue.push_back(start);
+            }
+        }
+        while let Some(point) = queue.pop_front() {
+            for neighbor in point.neighbors_in(neighborhood) {
+                if self.get(&neighbor).is_some_and(&passable) && !reached[&neighbor] {
+                    reached[&neighbor] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        reached
+    }
+}
+
+impl VoxelGrid<bool> {
+    /// Sized to fit every point, with the origin as the first corner.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point3>) -> Self {
+        let points: Vec<&Point3> = points.into_iter().collect();
+        let size = |axis: fn(&Point3) -> usize| points.iter().map(|p| axis(p) + 1).max();
+        let mut grid = Self::new(
+            size(|p| p.x).unwrap_or(0),
+            size(|p| p.y).unwrap_or(0),
+            size(|p| p.z).unwrap_or(0),
+            false,
+        );
+        for point in points {
+            grid[point] = true;
+        }
+        grid
+    }
+}
+
+impl<T> std::ops::Index<&Point3> for VoxelGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: &Point3) -> &Self::Output {
+        self.get(index).expect("point outside of voxel grid")
+    }
+}
+
+impl<T> std::ops::IndexMut<&Point3> for VoxelGrid<T> {
+    fn index_mut(&mut self, index: &Point3) -> &mut Self::Output {
+        self.get_mut(index).expect("point outside of voxel grid")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        assert_eq!(Vector3::<i8>::offsets(Neighborhood::Orthogonal).count(), 6);
+        assert_eq!(Vector3::<i8>::offsets(Neighborhood::All).count(), 26);
+        let a: Point3 = Point3 { x: 1, y: 5, z: 2 };
+        let b = Point3 { x: 4, y: 1, z: 2 };
+        assert_eq!(b - a, Vector3 { x: 3, y: -4, z: 0 });
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (7, 4));
+        assert_eq!(
+            Point3::<usize> { x: 0, y: 0, z: 0 }
+                .neighbors_in(Neighborhood::All)
+                .count(),
+            7
+        );
+    }
+
+    #[test]
+    fn lava_droplet() {
+        let input = "2,2,2\n1,2,2\n3,2,2\n2,1,2\n2,3,2\n2,2,1\n2,2,3\n2,2,4\n2,2,6\n1,2,5\n3,2,5\n2,1,5\n2,3,5\n";
+        // shifted by one so the flood fill can get around the droplet
+        let cubes: Vec<Point3> = input
+            .lines()
+            .map(|line| {
+                let c: Vec<usize> = line.split(',').map(|n| n.parse().unwrap()).collect();
+                Point3 {
+                    x: c[0] + 1,
+                    y: c[1] + 1,
+                    z: c[2] + 1,
+                }
+            })
+            .collect();
+        let bbox = BoundingBox3::from_points(&cubes).unwrap();
+        let mut lava = VoxelGrid::new(bbox.max.x + 2, bbox.max.y + 2, bbox.max.z + 2, false);
+        for cube in &cubes {
+            lava[cube] = true;
+        }
+        assert_eq!(VoxelGrid::from_points(&cubes).depth(), bbox.max.z + 1);
+
+        let faces = |open: &dyn Fn(&Point3) -> bool| -> usize {
+            cubes
+                .iter()
+                .flat_map(|cube| Vector3::offsets(Neighborhood::Orthogonal).map(move |v| *cube + v))
+                .filter(|p| open(p))
+                .count()
+        };
+        assert_eq!(faces(&|p| !lava[p]), 64);
+
+        let outside = lava.flood_fill(lava.boundary(), Neighborhood::Orthogonal, |cube| !cube);
+        assert_eq!(faces(&|p| outside[p]), 58);
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use super::terminal::{self, Key, RawMode};
+use super::Grid;
+
+/// A simulation that can be driven from the [`run`] terminal UI.
+pub trait Simulation: Clone {
+    type Cell: Display;
+
+    /// Advances the simulation by one automatic step. Returns `false` once
+    /// there is nothing left to do.
+    fn step(&mut self) -> bool;
+
+    /// Handles a key the UI does not use itself, e.g. arrow keys to issue a
+    /// move. Returns whether the state changed, so it can be undone.
+    fn input(&mut self, _key: Key) -> bool {
+        false
+    }
+
+    fn render(&self) -> Grid<Self::Cell>;
+
+    /// Name and value pairs shown next to the grid.
+    fn counters(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+}
+
+/// How many states [`Session`] keeps to undo, the oldest are dropped first.
+pub const UNDO_LIMIT: usize = 1000;
+
+/// The UI state around a simulation, without any terminal handling.
+#[derive(Debug, Clone)]
+pub struct Session<S> {
+    initial: S,
+    pub state: S,
+    history: VecDeque<S>,
+    pub running: bool,
+    pub finished: bool,
+    pub steps: usize,
+    pub quit: bool,
+}
+
+impl<S> Session<S>
+where
+    S: Simulation,
+{
+    pub fn new(state: S) -> Self {
+        Self {
+            initial: state.clone(),
+            state,
+            history: VecDeque::new(),
+            running: false,
+            finished: false,
+            steps: 0,
+            quit: false,
+        }
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            self.running = false;
+            return;
+        }
+        let before = self.state.clone();
+        if self.state.step() {
+            self.remember(before);
+            self.steps += 1;
+        } else {
+            self.finished = true;
+            self.running = false;
+        }
+    }
+
+    fn remember(&mut self, state: S) {
+        if self.history.len() == UNDO_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(state);
+    }
+
+    /// `n` steps, space runs or pauses, `u` undoes, `r` resets and `q` quits.
+    /// All other keys go to [`Simulation::input`].
+    pub fn handle(&mut self, key: Key) {
+        match key {
+            Key::Char('n') => {
+                self.running = false;
+                self.step();
+            }
+            Key::Char(' ') => self.running = !self.running && !self.finished,
+            Key::Char('u') => {
+                if let Some(previous) = self.history.pop_back() {
+                    self.state = previous;
+                    self.steps = self.steps.saturating_sub(1);
+                    self.finished = false;
+                }
+            }
+            Key::Char('r') => *self = Self::new(self.initial.clone()),
+            Key::Char('q') | Key::Escape => self.quit = true,
+            key => {
+                let before = self.state.clone();
+                if self.state.input(key) {
+                    self.remember(before);
+                    self.steps += 1;
+                    self.finished = false;
+                }
+            }
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.running {
+            self.step();
+        }
+    }
+
+    /// The grid with the side panel to its right.
+    pub fn screen(&self) -> String {
+        let grid = self.state.render().to_string();
+        let mut panel = vec![
+            format!("steps: {}", self.steps),
+            format!("undo: {}", self.history.len()),
+            format!(
+                "state: {}",
+                if self.finished {
+                    "finished"
+                } else if self.running {
+                    "running"
+                } else {
+                    "paused"
+                }
+            ),
+            String::new(),
+        ];
+        panel.extend(
+            self.state
+                .counters()
+                .into_iter()
+                .map(|(name, value)| format!("{name}: {value}")),
+        );
+        panel.push(String::new());
+        panel.push("n step, space run, u undo, r reset, q quit".to_owned());
+
+        let lines: Vec<&str> = grid.lines().collect();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut screen = String::new();
+        for i in 0..lines.len().max(panel.len()) {
+            let line = lines.get(i).copied().unwrap_or("");
+            let pad = width - line.chars().count();
+            let side = panel.get(i).map_or("", String::as_str);
+            screen.push_str(format!("{line}{:pad$}  {side}", "").trim_end());
+            screen.push('\n');
+        }
+        screen
+    }
+}
+
+/// Hosts the simulation in the terminal until `q` is pressed.
+pub fn run<S>(simulation: S) -> io::Result<()>
+where
+    S: Simulation,
+{
+    let raw = RawMode::enable()?;
+    let mut stdout = io::stdout();
+    let mut session = Session::new(simulation);
+    while !session.quit {
+        terminal::clear_screen(&mut stdout)?;
+        write!(stdout, "{}", session.screen())?;
+        stdout.flush()?;
+        match raw.read_key()? {
+            Some(key) => session.handle(key),
+            None => session.tick(),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helpers::Point;
+
+    #[derive(Debug, Clone)]
+    struct Walker {
+        position: Point,
+        moves: usize,
+    }
+
+    impl Simulation for Walker {
+        type Cell = char;
+
+        fn step(&mut self) -> bool {
+            if self.position.x < 2 {
+                self.position.x += 1;
+                true
+            } else {
+                false
+            }
+        }
+
+        fn input(&mut self, key: Key) -> bool {
+            match key {
+                Key::Down if self.position.y < 1 => {
+                    self.position.y += 1;
+                    self.moves += 1;
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        fn render(&self) -> Grid<char> {
+            let mut grid = Grid::new(3, 2, '.');
+            grid[&self.position] = '@';
+            grid
+        }
+
+        fn counters(&self) -> Vec<(String, String)> {
+            vec![("moves".to_owned(), self.moves.to_string())]
+        }
+    }
+
+    #[test]
+    fn session() {
+        let mut session = Session::new(Walker {
+            position: Point { x: 0, y: 0 },
+            moves: 0,
+        });
+        session.handle(Key::Char('n'));
+        session.handle(Key::Down);
+        session.handle(Key::Up);
+        assert_eq!(session.state.position, Point { x: 1, y: 1 });
+        assert_eq!(session.steps, 2);
+
+        session.handle(Key::Char('u'));
+        assert_eq!(session.state.position, Point { x: 1, y: 0 });
+
+        session.handle(Key::Char(' '));
+        session.tick();
+        session.tick();
+        assert!(session.finished && !session.running);
+        assert_eq!(session.state.position, Point { x: 2, y: 0 });
+
+        assert_eq!(
+            session.screen(),
+            "..@  steps: 2\n...  undo: 2\n     state: finished\n\n     moves: 0\n\n     n step, space run, u undo, r reset, q quit\n"
+        );
+
+        session.handle(Key::Char('r'));
+        assert_eq!((session.steps, session.state.position.x), (0, 0));
+    }
+
+    #[derive(Debug, Clone)]
+    struct Counter(usize);
+
+    impl Simulation for Counter {
+        type Cell = usize;
+
+        fn step(&mut self) -> bool {
+            self.0 += 1;
+            true
+        }
+
+        fn render(&self) -> Grid<usize> {
+            Grid::new(1, 1, self.0)
+        }
+    }
+
+    #[test]
+    fn bounded_undo() {
+        let mut session = Session::new(Counter(0));
+        for _ in 0..UNDO_LIMIT + 5 {
+            session.handle(Key::Char('n'));
+        }
+        for _ in 0..UNDO_LIMIT + 5 {
+            session.handle(Key::Char('u'));
+        }
+        assert_eq!(session.state.0, 5);
+    }
+}
@@ -4,11 +4,13 @@ files=(
     Cargo.toml
     Makefile
     src/helpers.rs
+    src/helpers/*.rs
     src/output.rs
     src/main.rs
     update-template.sh
 )
 
 for file in "${files[@]}" ; do
+    mkdir --parents "$(dirname ../../template/"${file}")"
     cp --verbose "${file}" ../../template/"${file}"
 done